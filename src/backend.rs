@@ -0,0 +1,162 @@
+use crate::layout::Layout;
+use crate::pyproject::{self, string_array};
+use std::fmt;
+use std::str::FromStr;
+use toml_edit::{value, DocumentMut, InlineTable};
+
+/// PEP 517 build backends supported by `hajime new`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Backend {
+    #[default]
+    Hatchling,
+    Setuptools,
+    Flit,
+    Pdm,
+    Maturin,
+    Uv,
+}
+
+impl FromStr for Backend {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "hatchling" => Ok(Backend::Hatchling),
+            "setuptools" => Ok(Backend::Setuptools),
+            "flit" => Ok(Backend::Flit),
+            "pdm" => Ok(Backend::Pdm),
+            "maturin" => Ok(Backend::Maturin),
+            "uv" => Ok(Backend::Uv),
+            _ => Err(format!(
+                "Unknown build backend '{}' (expected hatchling, setuptools, flit, pdm, maturin or uv)",
+                s
+            )),
+        }
+    }
+}
+
+impl fmt::Display for Backend {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            Backend::Hatchling => "hatchling",
+            Backend::Setuptools => "setuptools",
+            Backend::Flit => "flit",
+            Backend::Pdm => "pdm",
+            Backend::Maturin => "maturin",
+            Backend::Uv => "uv",
+        })
+    }
+}
+
+impl Backend {
+    pub const ALL: [Backend; 6] = [
+        Backend::Hatchling,
+        Backend::Setuptools,
+        Backend::Flit,
+        Backend::Pdm,
+        Backend::Maturin,
+        Backend::Uv,
+    ];
+
+    /// Requirements for `[build-system] requires`.
+    fn requires(self) -> &'static [&'static str] {
+        match self {
+            Backend::Hatchling => &["hatchling"],
+            Backend::Setuptools => &["setuptools>=77"],
+            Backend::Flit => &["flit_core>=3.12,<4"],
+            Backend::Pdm => &["pdm-backend"],
+            Backend::Maturin => &["maturin>=1.0,<2.0"],
+            Backend::Uv => &["uv_build>=0.8.0"],
+        }
+    }
+
+    /// Value of `[build-system] build-backend`.
+    fn build_backend(self) -> &'static str {
+        match self {
+            Backend::Hatchling => "hatchling.build",
+            Backend::Setuptools => "setuptools.build_meta",
+            Backend::Flit => "flit_core.buildapi",
+            Backend::Pdm => "pdm.backend",
+            Backend::Maturin => "maturin",
+            Backend::Uv => "uv_build",
+        }
+    }
+
+    /// Detects the backend from `[build-system] build-backend`, if it is one we know.
+    pub fn detect(doc: &DocumentMut) -> Option<Backend> {
+        let build_backend = pyproject::build_backend(doc)?;
+        Backend::ALL
+            .into_iter()
+            .find(|b| b.build_backend() == build_backend)
+    }
+
+    /// Writes `[build-system]` for this backend and removes the tool tables of other backends.
+    pub fn set_build_system(self, doc: &mut DocumentMut) {
+        let build_system = pyproject::table_mut(doc, &["build-system"]);
+        build_system["requires"] = value(string_array(self.requires()));
+        build_system["build-backend"] = value(self.build_backend());
+
+        for other in Backend::ALL.into_iter().filter(|b| *b != self) {
+            pyproject::remove(doc, &[&["tool"], other.tool_table()].concat());
+        }
+    }
+
+    /// Points this backend's tool table at the package directory for `layout`.
+    pub fn configure_packages(self, doc: &mut DocumentMut, layout: Layout, module_name: &str) {
+        let package_dir = layout.package_dir(module_name);
+        let table = pyproject::table_mut(doc, &[&["tool"], self.tool_table()].concat());
+
+        match self {
+            Backend::Hatchling => {
+                let wheel =
+                    pyproject::table_mut(doc, &["tool", "hatch", "build", "targets", "wheel"]);
+                wheel["packages"] = value(string_array(&[&package_dir]));
+            }
+            Backend::Setuptools => {
+                if layout == Layout::Src {
+                    let mut package_dirs = InlineTable::new();
+                    package_dirs.insert("", "src".into());
+                    table["package-dir"] = value(package_dirs);
+                }
+                let find = pyproject::table_mut(doc, &["tool", "setuptools", "packages", "find"]);
+                if layout == Layout::Src {
+                    find["where"] = value(string_array(&["src"]));
+                }
+                find["include"] = value(string_array(&[format!("{}*", module_name)]));
+            }
+            Backend::Flit => {
+                table["name"] = value(module_name);
+            }
+            Backend::Pdm => {
+                if layout == Layout::Src {
+                    table["package-dir"] = value("src");
+                }
+                table["includes"] = value(string_array(&[&package_dir]));
+            }
+            Backend::Maturin => {
+                if layout == Layout::Src {
+                    table["python-source"] = value("src");
+                }
+            }
+            Backend::Uv => {
+                table["module-name"] = value(module_name);
+                table["module-root"] = value(match layout {
+                    Layout::Flat => "",
+                    Layout::Src => "src",
+                });
+            }
+        }
+    }
+
+    /// The table below `[tool]` that configures this backend.
+    fn tool_table(self) -> &'static [&'static str] {
+        match self {
+            Backend::Hatchling => &["hatch"],
+            Backend::Setuptools => &["setuptools"],
+            Backend::Flit => &["flit", "module"],
+            Backend::Pdm => &["pdm", "build"],
+            Backend::Maturin => &["maturin"],
+            Backend::Uv => &["uv", "build-backend"],
+        }
+    }
+}
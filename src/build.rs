@@ -1,194 +1,174 @@
 use crate::helpers::{is_maturin_available, is_rust_python_project, is_uv_installed};
-use serde_json;
-use std::fs;
+use crate::pypi;
+use crate::version::{self, BumpLevel, Version};
+use std::io;
+use std::path::Path;
 use std::process::{Command, Stdio};
-use toml_edit::{value, DocumentMut};
 
-/// Fetches the latest version of the package from PyPI, if online.
-/// Returns `None` if the user is offline or if the package is not found.
-fn fetch_latest_pypi_version(package_name: &str) -> Option<String> {
-    let url = format!("https://pypi.org/pypi/{}/json", package_name);
-    let response = Command::new("curl").arg("-s").arg(&url).output();
-
-    if let Ok(output) = response {
-        if output.status.success() {
-            let json: serde_json::Value = serde_json::from_slice(&output.stdout).ok()?;
-            return json["info"]["version"].as_str().map(|s| s.to_string());
-        }
+/// Bumps the version in the specified file, or sets it to `set_version`, and
+/// returns the version to build.
+///
+/// # Arguments
+/// * `file_path` - The `pyproject.toml` or `Cargo.toml` holding the version.
+/// * `force_bump` - Level to bump by even if the current version is unpublished.
+///   Without it, the patch version is bumped only if the current version is
+///   already published.
+/// * `set_version` - Exact version to use instead of bumping.
+/// * `index_url` - JSON API URL of the index checked for the published version.
+fn bump_version(
+    file_path: &Path,
+    force_bump: Option<BumpLevel>,
+    set_version: Option<&Version>,
+    index_url: &str,
+) -> Result<String, String> {
+    let root = file_path.parent().unwrap_or(Path::new("."));
+    // With a version from git tags, the bump creates a tag instead of editing files
+    let vcs_version = !file_path.ends_with("Cargo.toml") && version::is_vcs_version(root);
+    if let (true, Some(set_version)) = (vcs_version, set_version) {
+        let tag = version::create_tag(root, set_version)
+            .map_err(|e| format!("Error tagging the version: {}", e))?;
+        println!("Created tag {}.", tag);
+        return Ok(set_version.to_string());
     }
-    None
-}
-
-/// Bumps the version in the specified file.
-/// `level` can be "macro", "meso", or "micro".
-fn bump_version(file_path: &str, force_bump: Option<&str>) -> Result<String, String> {
-    let content = fs::read_to_string(file_path)
-        .map_err(|e| format!("Error reading file {}: {}", file_path, e))?;
-    let mut doc = content
-        .parse::<DocumentMut>()
-        .map_err(|e| format!("Error parsing TOML: {}", e))?;
-
-    // Extract project name and version
-    // Check if this is a Rust or Python project
-    let is_rust_project = file_path.ends_with("Cargo.toml");
-
-    // Extract project name and version
-    let name_key = if is_rust_project {
-        "package"
+    let git_version = if vcs_version {
+        Some(version::git_version(root).map_err(|e| e.to_string())?)
     } else {
-        "project"
+        None
     };
-    let project_name = doc[name_key]["name"]
-        .as_str()
-        .ok_or(format!("No project name found in {}", file_path))?;
-    let version = doc[name_key]["version"]
-        .as_str()
-        .ok_or("No version field found in TOML")?;
 
-    // Check the latest PyPI version
-    let mut should_bump = false;
-    if let Some(latest_version) = fetch_latest_pypi_version(project_name) {
-        if version == latest_version {
-            should_bump = true;
-            println!(
-                "The current version ({}) is already published on PyPI.",
-                version
-            );
+    let (project_name, version_str) = match &git_version {
+        Some(git_version) => (
+            version::read_name(file_path).map_err(|e| e.to_string())?,
+            git_version.current.to_string(),
+        ),
+        None => version::read_raw(file_path).map_err(|e| e.to_string())?,
+    };
+    let version: Version = version_str
+        .parse()
+        .map_err(|e| format!("Invalid version in {}: {}", file_path.display(), e))?;
+
+    let new_version = if let Some(set_version) = set_version {
+        set_version.clone()
+    } else {
+        // Check the latest PyPI version
+        let mut should_bump = false;
+        if let Some(latest_version) = pypi::latest_version(index_url, &project_name) {
+            if latest_version
+                .parse::<Version>()
+                .is_ok_and(|v| v == version)
+            {
+                should_bump = true;
+                println!(
+                    "The current version ({}) is already published on PyPI.",
+                    version
+                );
+            } else {
+                println!(
+                    "The current version ({}) is not on PyPI (latest: {}).",
+                    version, latest_version
+                );
+            }
         } else {
-            println!(
-                "The current version ({}) is not on PyPI (latest: {}).",
-                version, latest_version
-            );
+            println!("Skipping PyPI version check (offline or package not found).");
         }
-    } else {
-        println!("Skipping PyPI version check (offline or package not found).");
-    }
-
-    // Determine if we need to bump the version
-    if !should_bump && force_bump.is_none() {
-        println!("Version bump not required. Using version {}.", version);
-        return Ok(version.to_string());
-    }
-
-    // If forced bump level is specified, use it; otherwise, bump micro by default
-    let level = force_bump.unwrap_or("micro");
-    // Split and bump the version
-    let mut parts: Vec<u32> = version
-        .split('.')
-        .map(|s| s.parse::<u32>().unwrap_or(0))
-        .collect();
-
-    // Ensure version is at least three parts long
-    while parts.len() < 3 {
-        parts.push(0);
-    }
-
-    // Bump the version
-    match level {
-        "macro" => parts[0] += 1,
-        "meso" => parts[1] += 1,
-        "micro" => parts[2] += 1,
-        _ => return Err("Invalid version bump level".into()),
-    }
 
-    // Reset lower parts after a bump
-    match level {
-        "macro" => {
-            parts[1] = 0;
-            parts[2] = 0;
+        // Determine if we need to bump the version
+        if !should_bump && force_bump.is_none() {
+            println!("Version bump not required. Using version {}.", version_str);
+            return Ok(version_str);
         }
-        "meso" => {
-            parts[2] = 0;
-        }
-        _ => {}
-    }
 
-    let new_version = format!("{}.{}.{}", parts[0], parts[1], parts[2]);
-    doc["project"]["version"] = value(&new_version);
+        // If forced bump level is specified, use it; otherwise, bump patch by default
+        let base = git_version.as_ref().map_or(&version, |g| &g.tag);
+        base.bump(force_bump.unwrap_or(BumpLevel::Patch))?
+    };
 
-    fs::write(file_path, doc.to_string())
-        .map_err(|e| format!("Error writing file {}: {}", file_path, e))?;
+    if git_version.is_some() {
+        let tag = version::create_tag(root, &new_version)
+            .map_err(|e| format!("Error tagging the version: {}", e))?;
+        println!("Created tag {}.", tag);
+    } else {
+        version::version_files(root, file_path)
+            .and_then(|files| version::write_all(&files, &new_version))
+            .map_err(|e| format!("Error writing the version: {}", e))?;
+    }
 
-    Ok(new_version)
+    Ok(new_version.to_string())
 }
 
-pub fn build_project(use_maturin: bool, bump_version_level: Option<&str>) {
-    if use_maturin || is_rust_python_project() {
-        let cargo_toml_path = "Cargo.toml";
-        match bump_version(cargo_toml_path, bump_version_level) {
-            Ok(new_version) => println!("Using version {}", new_version),
-            Err(e) => {
-                eprintln!("Error: {}", e);
-                return;
-            }
-        }
-        build_with_maturin();
+/// Builds the project in `root`, bumping its version first if needed.
+pub fn build_project(
+    root: &Path,
+    use_maturin: bool,
+    bump_version_level: Option<BumpLevel>,
+    set_version: Option<&Version>,
+    index_url: &str,
+) -> io::Result<()> {
+    let maturin = use_maturin || is_rust_python_project(root);
+    let version_file = if maturin {
+        root.join("Cargo.toml")
     } else {
-        let pyproject_toml_path = "pyproject.toml";
-        match bump_version(pyproject_toml_path, bump_version_level) {
-            Ok(new_version) => println!("Using version {}", new_version),
-            Err(e) => {
-                eprintln!("Error: {}", e);
-                return;
-            }
-        }
-        build_with_uv();
+        root.join("pyproject.toml")
+    };
+    let new_version = bump_version(&version_file, bump_version_level, set_version, index_url)
+        .map_err(io::Error::other)?;
+    println!("Using version {}", new_version);
+
+    if maturin {
+        build_with_maturin(root)
+    } else {
+        build_with_uv(root)
     }
 }
 
-fn build_with_uv() {
+fn build_with_uv(root: &Path) -> io::Result<()> {
     if !is_uv_installed() {
-        eprintln!("Error: `uv` is not installed or not found in PATH.");
+        return Err(io::Error::new(
+            io::ErrorKind::NotFound,
+            "`uv` is not installed or not found in PATH.",
+        ));
     }
 
     println!("Building the Python project using `uv build`...");
-    let command = Command::new("uv")
-        .arg("build")
+    let status = Command::new("uv")
+        .args(["build", "--out-dir", "dist"])
+        .current_dir(root)
         .stdout(Stdio::inherit())
         .stderr(Stdio::inherit())
-        .spawn();
+        .status()
+        .map_err(|e| io::Error::other(format!("Error running uv: {}", e)))?;
 
-    match command {
-        Ok(mut child) => {
-            let status = child.wait().expect("Failed to wait on child process");
-            if status.success() {
-                println!("Build successful!");
-            } else {
-                eprintln!(
-                    "Build failed. If `build` is missing, install it with `pip install build`."
-                );
-            }
-        }
-        Err(e) => {
-            eprintln!("Error running command: {}", e);
-        }
+    if !status.success() {
+        return Err(io::Error::other(
+            "Build failed. If `build` is missing, install it with `pip install build`.",
+        ));
     }
+    println!("Build successful!");
+    Ok(())
 }
 
-fn build_with_maturin() {
+fn build_with_maturin(root: &Path) -> io::Result<()> {
     if !is_maturin_available() {
-        eprintln!("Error: `maturin` is not installed or not found in PATH.");
-        return;
+        return Err(io::Error::new(
+            io::ErrorKind::NotFound,
+            "`maturin` is not installed or not found in PATH.",
+        ));
     }
 
     println!("Building the Python project using `maturin build --release`...");
-    let command = Command::new("maturin")
-        .args(&["build", "--release"])
+    let status = Command::new("maturin")
+        .args(["build", "--release"])
+        .current_dir(root)
         .stdout(Stdio::inherit())
         .stderr(Stdio::inherit())
-        .spawn();
+        .status()
+        .map_err(|e| io::Error::other(format!("Error running maturin: {}", e)))?;
 
-    match command {
-        Ok(mut child) => {
-            let status = child.wait().expect("Failed to wait on child process");
-            if status.success() {
-                println!("Build successful!");
-            } else {
-                eprintln!("Build failed. Check the output above for details.");
-            }
-        }
-        Err(e) => {
-            eprintln!("Error running command: {}", e);
-        }
+    if !status.success() {
+        return Err(io::Error::other(
+            "Build failed. Check the output above for details.",
+        ));
     }
+    println!("Build successful!");
+    Ok(())
 }
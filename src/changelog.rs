@@ -0,0 +1,419 @@
+use crate::git;
+use crate::helpers::utc_now;
+use crate::version::{BumpLevel, Version};
+use std::fs;
+use std::io;
+use std::path::Path;
+
+pub const CHANGELOG_FILE: &str = "CHANGELOG.md";
+
+/// Heading of the section for changes that are not released yet.
+const UNRELEASED: &str = "Unreleased";
+
+/// Length of the commit hashes written in entries. It is fixed, rather than
+/// left to `core.abbrev`, so entries stay comparable across runs.
+const HASH_LENGTH: usize = 12;
+
+/// Changelog sections, in the order they are written.
+const SECTIONS: [&str; 4] = ["Breaking Changes", "Added", "Fixed", "Performance"];
+
+/// Written at the top of a new `CHANGELOG.md`.
+const HEADER: &str = "# Changelog
+
+All notable changes to this project will be documented in this file.
+
+The format is based on [Keep a Changelog](https://keepachangelog.com/en/1.1.0/),
+and this project uses [PEP 440](https://peps.python.org/pep-0440/) versions.
+";
+
+/// A commit message following the Conventional Commits specification,
+/// e.g. `feat(cli)!: drop the --legacy flag`.
+struct Commit {
+    /// Full commit hash.
+    hash: String,
+    kind: String,
+    scope: Option<String>,
+    description: String,
+    breaking: bool,
+}
+
+impl Commit {
+    /// Parses `subject` and `body`; `None` if the subject is not a Conventional Commit.
+    fn parse(hash: &str, subject: &str, body: &str) -> Option<Commit> {
+        let (prefix, description) = subject.split_once(": ")?;
+        let (prefix, bang) = match prefix.strip_suffix('!') {
+            Some(prefix) => (prefix, true),
+            None => (prefix, false),
+        };
+        let (kind, scope) = match prefix.split_once('(') {
+            Some((kind, scope)) => (kind, Some(scope.strip_suffix(')')?.to_string())),
+            None => (prefix, None),
+        };
+        if kind.is_empty() || !kind.chars().all(|c| c.is_ascii_alphabetic()) {
+            return None;
+        }
+        let breaking = bang
+            || body
+                .lines()
+                .any(|l| l.starts_with("BREAKING CHANGE:") || l.starts_with("BREAKING-CHANGE:"));
+        Some(Commit {
+            hash: hash.to_string(),
+            kind: kind.to_ascii_lowercase(),
+            scope,
+            description: description.trim().to_string(),
+            breaking,
+        })
+    }
+
+    /// The changelog section the commit is listed in, if any.
+    fn section(&self) -> Option<&'static str> {
+        if self.breaking {
+            return Some("Breaking Changes");
+        }
+        match self.kind.as_str() {
+            "feat" => Some("Added"),
+            "fix" => Some("Fixed"),
+            "perf" => Some("Performance"),
+            _ => None,
+        }
+    }
+
+    /// The hash shown in the changelog, [`HASH_LENGTH`] characters long.
+    fn short_hash(&self) -> &str {
+        &self.hash[..HASH_LENGTH.min(self.hash.len())]
+    }
+
+    /// Whether one of the abbreviated `hashes` refers to this commit.
+    fn is_listed(&self, hashes: &[&str]) -> bool {
+        hashes.iter().any(|h| self.hash.starts_with(h))
+    }
+
+    /// The changelog entry, e.g. `- **cli:** drop the --legacy flag (0a1b2c3d4e5f)`.
+    fn entry(&self) -> String {
+        match &self.scope {
+            Some(scope) => format!(
+                "- **{}:** {} ({})",
+                scope,
+                self.description,
+                self.short_hash()
+            ),
+            None => format!("- {} ({})", self.description, self.short_hash()),
+        }
+    }
+}
+
+/// The most recent release tag (`vX.Y.Z` or `X.Y.Z`) reachable from `HEAD`.
+fn last_release_tag(root: &Path) -> Option<String> {
+    git::run(
+        root,
+        &[
+            "describe",
+            "--tags",
+            "--abbrev=0",
+            "--match",
+            "v[0-9]*",
+            "--match",
+            "[0-9]*",
+        ],
+    )
+    .ok()
+}
+
+/// The Conventional Commits since the last release tag, newest first, and that tag.
+fn commits_since_release(root: &Path) -> io::Result<(Vec<Commit>, Option<String>)> {
+    let tag = last_release_tag(root);
+    let range = match &tag {
+        Some(tag) => format!("{}..HEAD", tag),
+        None => "HEAD".to_string(),
+    };
+    let log = git::run(root, &["log", "--format=%H%x1f%s%x1f%b%x1e", &range])?;
+    let commits = log
+        .split('\x1e')
+        .filter_map(|record| {
+            let mut fields = record.trim_start_matches('\n').splitn(3, '\x1f');
+            let hash = fields.next()?;
+            let subject = fields.next()?;
+            Commit::parse(hash, subject, fields.next().unwrap_or(""))
+        })
+        .collect();
+    Ok((commits, tag))
+}
+
+/// The Keep a Changelog section for `commits` under `title`, or `None` if no
+/// commit is a feature, fix, performance improvement or breaking change.
+fn render_section(title: &str, commits: &[Commit]) -> Option<String> {
+    let heading = if title == UNRELEASED {
+        format!("## [{}]", UNRELEASED)
+    } else {
+        let (year, month, day, ..) = utc_now();
+        format!("## [{}] - {:04}-{:02}-{:02}", title, year, month, day)
+    };
+    let mut section = heading;
+    let mut any = false;
+    for name in SECTIONS {
+        let entries: Vec<String> = commits
+            .iter()
+            .filter(|c| c.section() == Some(name))
+            .map(Commit::entry)
+            .collect();
+        if entries.is_empty() {
+            continue;
+        }
+        any = true;
+        section.push_str(&format!("\n\n### {}\n\n{}", name, entries.join("\n")));
+    }
+    any.then(|| section + "\n")
+}
+
+/// Abbreviated hashes of the commits listed in the released sections of `changelog`.
+/// The `Unreleased` section is left out, since it is rewritten on every run.
+fn released_hashes(changelog: &str) -> Vec<&str> {
+    let unreleased = format!("## [{}]", UNRELEASED);
+    let mut in_unreleased = false;
+    let mut hashes = Vec::new();
+    for line in changelog.lines() {
+        if line.starts_with("## ") {
+            in_unreleased = line.trim() == unreleased;
+        } else if !in_unreleased && line.starts_with("- ") {
+            let hash = line
+                .strip_suffix(')')
+                .and_then(|l| l.rsplit_once('('))
+                .map(|(_, hash)| hash);
+            // Git never abbreviates hashes to fewer than four characters
+            let hash = hash.filter(|h| h.len() >= 4 && h.chars().all(|c| c.is_ascii_hexdigit()));
+            if let Some(hash) = hash {
+                hashes.push(hash);
+            }
+        }
+    }
+    hashes
+}
+
+/// Inserts `section` into `changelog` before the first release section,
+/// replacing an existing `Unreleased` section.
+fn insert_section(changelog: &str, section: &str) -> String {
+    let mut lines: Vec<&str> = changelog.lines().collect();
+    let unreleased = format!("## [{}]", UNRELEASED);
+    if let Some(start) = lines.iter().position(|l| l.trim() == unreleased) {
+        let end = lines[start + 1..]
+            .iter()
+            .position(|l| l.starts_with("## "))
+            .map_or(lines.len(), |i| start + 1 + i);
+        lines.drain(start..end);
+    }
+
+    let at = lines
+        .iter()
+        .position(|l| l.starts_with("## "))
+        .unwrap_or(lines.len());
+    let before = lines[..at].join("\n");
+    let after = lines[at..].join("\n");
+    let mut result = format!("{}\n\n{}", before.trim_end(), section);
+    if !after.is_empty() {
+        result.push('\n');
+        result.push_str(&after);
+        result.push('\n');
+    }
+    result
+}
+
+/// Writes the changes since the last release tag to `CHANGELOG.md` in `root`,
+/// grouped by Conventional Commit type.
+///
+/// # Arguments
+/// * `root` - The project root.
+/// * `release` - Version to title the section with; `Unreleased` if not given.
+/// * `dry_run` - Print the section instead of writing it.
+pub fn generate(root: &Path, release: Option<&Version>, dry_run: bool) -> io::Result<()> {
+    let path = root.join(CHANGELOG_FILE);
+    let existing = if path.exists() {
+        fs::read_to_string(&path)?
+    } else {
+        HEADER.to_string()
+    };
+
+    // Commits released in the file but not tagged yet are not listed again
+    let (mut commits, tag) = commits_since_release(root)?;
+    let listed = released_hashes(&existing);
+    commits.retain(|c| !c.is_listed(&listed));
+    let since = tag.as_deref().unwrap_or("the first commit");
+    let title = release.map_or(UNRELEASED.to_string(), Version::to_string);
+    let Some(section) = render_section(&title, &commits) else {
+        println!(
+            "No features, fixes, performance improvements or breaking changes since {} that {} does not list yet.",
+            since, CHANGELOG_FILE
+        );
+        return Ok(());
+    };
+
+    if dry_run {
+        print!("{}", section);
+        return Ok(());
+    }
+
+    if release.is_some()
+        && existing
+            .lines()
+            .any(|l| l.starts_with(&format!("## [{}]", title)))
+    {
+        return Err(io::Error::new(
+            io::ErrorKind::AlreadyExists,
+            format!("{} already has a section for {}.", CHANGELOG_FILE, title),
+        ));
+    }
+    fs::write(&path, insert_section(&existing, &section))?;
+    println!(
+        "Added the changes since {} to {} under [{}].",
+        since, CHANGELOG_FILE, title
+    );
+    Ok(())
+}
+
+/// The bump level the commits since the last release call for: major for
+/// breaking changes (minor before 1.0), minor for features, else patch.
+pub fn release_level(root: &Path, current: &Version) -> io::Result<BumpLevel> {
+    let (commits, tag) = commits_since_release(root)?;
+    level_for(&commits, current).ok_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::NotFound,
+            format!(
+                "No features, fixes, performance improvements or breaking changes since {}; nothing to release.",
+                tag.as_deref().unwrap_or("the first commit")
+            ),
+        )
+    })
+}
+
+/// The bump level for `commits` on top of `current`, if any of them is releasable.
+fn level_for(commits: &[Commit], current: &Version) -> Option<BumpLevel> {
+    if commits.iter().any(|c| c.breaking) {
+        if current.release.first() == Some(&0) {
+            Some(BumpLevel::Minor)
+        } else {
+            Some(BumpLevel::Major)
+        }
+    } else if commits.iter().any(|c| c.kind == "feat") {
+        Some(BumpLevel::Minor)
+    } else if commits.iter().any(|c| c.section().is_some()) {
+        Some(BumpLevel::Patch)
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn commit(subject: &str) -> Commit {
+        Commit::parse("abc1234", subject, "").unwrap()
+    }
+
+    #[test]
+    fn parse_conventional_commits() {
+        let c = commit("feat(cli): add a flag");
+        assert_eq!(c.kind, "feat");
+        assert_eq!(c.scope.as_deref(), Some("cli"));
+        assert_eq!(c.description, "add a flag");
+        assert!(!c.breaking);
+        assert_eq!(c.entry(), "- **cli:** add a flag (abc1234)");
+
+        let c = commit("Fix: crash on start");
+        assert_eq!(c.kind, "fix");
+        assert_eq!(c.scope, None);
+        assert_eq!(c.entry(), "- crash on start (abc1234)");
+    }
+
+    #[test]
+    fn parse_breaking_changes() {
+        assert!(commit("refactor!: drop the api").breaking);
+        assert!(commit("feat(cli)!: drop --legacy").breaking);
+        let footer = Commit::parse("abc1234", "feat: new config", "BREAKING CHANGE: moved");
+        assert!(footer.unwrap().breaking);
+        let footer = Commit::parse("abc1234", "fix: x", "Details.\n\nBREAKING-CHANGE: y");
+        assert!(footer.unwrap().breaking);
+    }
+
+    #[test]
+    fn parse_rejects_other_subjects() {
+        assert!(Commit::parse("abc1234", "Merge branch 'main'", "").is_none());
+        assert!(Commit::parse("abc1234", "feat(cli: missing paren", "").is_none());
+        assert!(Commit::parse("abc1234", "release 1.0: done", "").is_none());
+        assert!(Commit::parse("abc1234", ": empty type", "").is_none());
+    }
+
+    #[test]
+    fn sections_group_commits() {
+        let commits = [
+            commit("fix: b"),
+            commit("docs: c"),
+            commit("feat: a"),
+            commit("feat!: d"),
+        ];
+        assert_eq!(
+            render_section(UNRELEASED, &commits).unwrap(),
+            "## [Unreleased]\n\n### Breaking Changes\n\n- d (abc1234)\n\n### Added\n\n- a (abc1234)\n\n### Fixed\n\n- b (abc1234)\n"
+        );
+        assert!(render_section(UNRELEASED, &[commit("chore: x")]).is_none());
+    }
+
+    #[test]
+    fn insert_into_new_changelog() {
+        let section = "## [Unreleased]\n\n### Added\n\n- a (abc1234)\n";
+        assert_eq!(
+            insert_section(HEADER, section),
+            format!("{}\n{}", HEADER, section)
+        );
+    }
+
+    #[test]
+    fn insert_before_previous_releases() {
+        let changelog = "# Changelog\n\n## [1.0.0] - 2026-01-01\n\n### Fixed\n\n- b (1111111)\n";
+        let section = "## [1.1.0] - 2026-02-01\n\n### Added\n\n- a (2222222)\n";
+        assert_eq!(
+            insert_section(changelog, section),
+            "# Changelog\n\n## [1.1.0] - 2026-02-01\n\n### Added\n\n- a (2222222)\n\n## [1.0.0] - 2026-01-01\n\n### Fixed\n\n- b (1111111)\n"
+        );
+    }
+
+    #[test]
+    fn insert_replaces_unreleased_section() {
+        let changelog = "# Changelog\n\n## [Unreleased]\n\n### Fixed\n\n- old (1111111)\n\n## [1.0.0] - 2026-01-01\n\n- c (3333333)\n";
+        let section = "## [Unreleased]\n\n### Fixed\n\n- new (2222222)\n";
+        assert_eq!(
+            insert_section(changelog, section),
+            "# Changelog\n\n## [Unreleased]\n\n### Fixed\n\n- new (2222222)\n\n## [1.0.0] - 2026-01-01\n\n- c (3333333)\n"
+        );
+    }
+
+    #[test]
+    fn released_hashes_skip_unreleased() {
+        let changelog = "# Changelog\n\n## [Unreleased]\n\n- a (1111111)\n\n## [1.0.0] - 2026-01-01\n\n### Added\n\n- **cli:** b (2222222)\n- c (see #12)\n";
+        assert_eq!(released_hashes(changelog), ["2222222"]);
+    }
+
+    #[test]
+    fn entries_use_fixed_length_hashes() {
+        let c = Commit::parse("0a1b2c3d4e5f60718293a4b5c6d7e8f901234567", "fix: x", "").unwrap();
+        assert_eq!(c.entry(), "- x (0a1b2c3d4e5f)");
+        // Entries written with a different abbreviation still match
+        assert!(c.is_listed(&["0a1b2c3"]));
+        assert!(c.is_listed(&["0a1b2c3d4e5f"]));
+        assert!(!c.is_listed(&["0a1b2c4"]));
+    }
+
+    #[test]
+    fn release_level_from_commit_types() {
+        let v1: Version = "1.2.3".parse().unwrap();
+        let v0: Version = "0.4.0".parse().unwrap();
+        let level = |subjects: &[&str], current: &Version| {
+            let commits: Vec<Commit> = subjects.iter().map(|s| commit(s)).collect();
+            level_for(&commits, current)
+        };
+        assert_eq!(level(&["fix: a", "feat!: b"], &v1), Some(BumpLevel::Major));
+        assert_eq!(level(&["feat!: b"], &v0), Some(BumpLevel::Minor));
+        assert_eq!(level(&["fix: a", "feat: b"], &v1), Some(BumpLevel::Minor));
+        assert_eq!(level(&["perf: a", "docs: b"], &v1), Some(BumpLevel::Patch));
+        assert_eq!(level(&["docs: a", "chore: b"], &v1), None);
+    }
+}
@@ -1,12 +1,16 @@
+use crate::pyproject;
 use std::io;
 use std::path::Path;
 use std::process::{Command, Stdio};
 
-pub fn check_package() -> io::Result<()> {
+/// Checks the built distributions and metadata of the project in `root`.
+pub fn check_package(root: &Path) -> io::Result<()> {
+    check_license(&root.join("pyproject.toml"))?;
+
     // Determine the directory to check (dist or target/wheels)
-    let dist_dir = if Path::new("dist").exists() {
+    let dist_dir = if root.join("dist").exists() {
         "dist/*"
-    } else if Path::new("target/wheels").exists() {
+    } else if root.join("target/wheels").exists() {
         "target/wheels/*"
     } else {
         return Err(io::Error::new(
@@ -19,7 +23,8 @@ pub fn check_package() -> io::Result<()> {
 
     // Run twine check on the determined directory
     let command = Command::new("uv")
-        .args(&["run", "twine", "check", dist_dir])
+        .args(["run", "twine", "check", dist_dir])
+        .current_dir(root)
         .stdout(Stdio::inherit())
         .stderr(Stdio::inherit())
         .spawn();
@@ -30,19 +35,40 @@ pub fn check_package() -> io::Result<()> {
             if status.success() {
                 println!("Twine check passed successfully!");
             } else {
-                return Err(io::Error::new(
-                    io::ErrorKind::Other,
+                return Err(io::Error::other(
                     "Twine check failed. Check the output above for details.",
                 ));
             }
         }
         Err(e) => {
-            return Err(io::Error::new(
-                io::ErrorKind::Other,
-                format!("Error running twine check: {}", e),
-            ));
+            return Err(io::Error::other(format!(
+                "Error running twine check: {}",
+                e
+            )));
         }
     }
 
     Ok(())
 }
+
+/// Fails if `pyproject.toml` declares no license, which twine does not catch.
+fn check_license(path: &Path) -> io::Result<()> {
+    if !path.exists() {
+        return Ok(());
+    }
+    let doc = pyproject::read(path)?;
+    let Some(project) = doc.get("project") else {
+        return Ok(());
+    };
+    let dynamic = project
+        .get("dynamic")
+        .and_then(|d| d.as_array())
+        .is_some_and(|d| d.iter().any(|v| v.as_str() == Some("license")));
+    if project.get("license").is_none() && !dynamic {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "pyproject.toml has no `license`. Set one with `hajime license set <id>`.",
+        ));
+    }
+    Ok(())
+}
@@ -0,0 +1,266 @@
+use crate::git;
+use crate::new::DEFAULT_PYTHON_VERSION;
+use crate::pyproject;
+use crate::template::TemplateContext;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+/// Newest Python minor version included in generated test matrices.
+pub const LATEST_PYTHON_MINOR: u32 = 14;
+
+/// CI services `hajime` can generate a workflow for.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum CiProvider {
+    #[default]
+    Github,
+    Gitlab,
+}
+
+impl FromStr for CiProvider {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "github" => Ok(CiProvider::Github),
+            "gitlab" => Ok(CiProvider::Gitlab),
+            _ => Err(format!(
+                "Unknown CI provider '{}' (expected github or gitlab)",
+                s
+            )),
+        }
+    }
+}
+
+impl fmt::Display for CiProvider {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            CiProvider::Github => "github",
+            CiProvider::Gitlab => "gitlab",
+        })
+    }
+}
+
+impl CiProvider {
+    /// Where the workflow file lives, relative to the project root.
+    pub fn path(self) -> PathBuf {
+        match self {
+            CiProvider::Github => PathBuf::from(".github/workflows/ci.yml"),
+            CiProvider::Gitlab => PathBuf::from(".gitlab-ci.yml"),
+        }
+    }
+
+    fn template(self) -> &'static str {
+        match self {
+            CiProvider::Github => include_str!("../templates/ci/github.yml"),
+            CiProvider::Gitlab => include_str!("../templates/ci/gitlab.yml"),
+        }
+    }
+
+    /// Renders the workflow for the given `requires-python` specifier, running
+    /// on pushes to `branch`.
+    fn render(self, requires_python: &str, branch: &str) -> io::Result<String> {
+        let versions = python_matrix(requires_python)?;
+        let quoted: Vec<String> = versions.iter().map(|v| format!("\"{}\"", v)).collect();
+
+        let mut context = TemplateContext::default();
+        context.set("branch", branch);
+        context.set("python_versions", &format!("[{}]", quoted.join(", ")));
+        context.set(
+            "latest_python",
+            versions.last().expect("matrix is not empty"),
+        );
+        Ok(context.render(self.template()))
+    }
+
+    /// Writes the workflow for the project in `dir`, using its `requires-python`
+    /// and the default `branch`. An existing workflow file is only replaced with
+    /// `force`.
+    pub fn write(self, dir: &Path, branch: &str, force: bool) -> io::Result<PathBuf> {
+        let doc = pyproject::read(&dir.join("pyproject.toml"))?;
+        let requires_python = doc
+            .get("project")
+            .and_then(|p| p.get("requires-python"))
+            .and_then(|r| r.as_str())
+            .map(str::to_string)
+            .unwrap_or_else(|| format!(">={}", DEFAULT_PYTHON_VERSION));
+
+        let path = dir.join(self.path());
+        if path.exists() && !force {
+            return Err(io::Error::new(
+                io::ErrorKind::AlreadyExists,
+                format!(
+                    "{} already exists; use --force to replace it.",
+                    self.path().display()
+                ),
+            ));
+        }
+        let workflow = self.render(&requires_python, branch)?;
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(&path, workflow)?;
+        Ok(path)
+    }
+}
+
+/// The Python versions (`3.X`) allowed by a `requires-python` specifier,
+/// capped at [`LATEST_PYTHON_MINOR`] unless the lower bound is newer.
+fn python_matrix(requires_python: &str) -> io::Result<Vec<String>> {
+    let invalid = || {
+        io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!(
+                "Cannot derive a Python version matrix from requires-python '{}'.",
+                requires_python
+            ),
+        )
+    };
+
+    let mut lowest = DEFAULT_PYTHON_VERSION
+        .split_once('.')
+        .and_then(|(_, minor)| minor.parse().ok())
+        .unwrap_or(9);
+    let mut highest: Option<u32> = None;
+    for specifier in requires_python.split(',').map(str::trim) {
+        if specifier.is_empty() {
+            continue;
+        }
+        let operator_len = specifier
+            .find(|c: char| c.is_ascii_digit())
+            .ok_or_else(invalid)?;
+        let (operator, version) = specifier.split_at(operator_len);
+        let operator = operator.trim();
+        let parts: Vec<&str> = version.trim_end_matches(".*").split('.').collect();
+        let major: u32 = parts[0].parse().map_err(|_| invalid())?;
+        if major != 3 {
+            // `<4`, `<=4.0` or `>=2.7` do not narrow the Python 3 versions
+            match operator {
+                "<" | "<=" | "!=" if major > 3 => continue,
+                ">" | ">=" | "!=" if major < 3 => continue,
+                _ => return Err(invalid()),
+            }
+        }
+        let minor: u32 = parts
+            .get(1)
+            .unwrap_or(&"0")
+            .parse()
+            .map_err(|_| invalid())?;
+        let has_patch = parts.get(2).is_some_and(|p| *p != "0");
+
+        match operator {
+            ">=" => lowest = minor,
+            // `~=3.9.1` means `>=3.9.1, ==3.9.*`; `~=3.9` means `>=3.9, ==3.*`
+            "~=" if parts.len() > 2 => {
+                lowest = minor;
+                highest = Some(minor);
+            }
+            "~=" if parts.len() == 2 => lowest = minor,
+            ">" if has_patch => lowest = minor,
+            ">" => lowest = minor + 1,
+            "<=" => highest = Some(minor),
+            "<" if has_patch => highest = Some(minor),
+            "<" => highest = Some(minor.saturating_sub(1)),
+            "==" => {
+                lowest = minor;
+                highest = Some(minor);
+            }
+            "!=" => {}
+            _ => return Err(invalid()),
+        }
+    }
+
+    let highest = highest
+        .unwrap_or(LATEST_PYTHON_MINOR)
+        .min(LATEST_PYTHON_MINOR.max(lowest));
+    let versions: Vec<String> = (lowest..=highest).map(|m| format!("3.{}", m)).collect();
+    if versions.is_empty() {
+        return Err(invalid());
+    }
+    Ok(versions)
+}
+
+/// Writes (or with `force`, replaces) the CI workflow of the project in `root`,
+/// for the branch its repository is on.
+pub fn generate(root: &Path, provider: CiProvider, force: bool) -> io::Result<()> {
+    provider.write(root, &git::default_branch(root), force)?;
+    println!(
+        "Wrote {} CI workflow to {}.",
+        provider,
+        provider.path().display()
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn matrix(requires_python: &str) -> Vec<String> {
+        python_matrix(requires_python).unwrap()
+    }
+
+    #[test]
+    fn matrix_from_lower_bound() {
+        assert_eq!(matrix(">=3.12"), ["3.12", "3.13", "3.14"]);
+        assert_eq!(matrix(">3.12"), ["3.13", "3.14"]);
+        assert_eq!(matrix(">3.12.1"), ["3.12", "3.13", "3.14"]);
+    }
+
+    #[test]
+    fn matrix_with_upper_bound() {
+        assert_eq!(matrix(">=3.9,<3.12"), ["3.9", "3.10", "3.11"]);
+        assert_eq!(matrix(">=3.9, <=3.10"), ["3.9", "3.10"]);
+        assert_eq!(matrix(">=3.9,<3.11.2"), ["3.9", "3.10", "3.11"]);
+        assert_eq!(matrix("==3.11.*"), ["3.11"]);
+    }
+
+    #[test]
+    fn matrix_ignores_bounds_outside_python_3() {
+        assert_eq!(matrix(">=3.12,<4"), ["3.12", "3.13", "3.14"]);
+        assert_eq!(matrix(">=3.12,<4.0"), ["3.12", "3.13", "3.14"]);
+        assert_eq!(matrix(">=2.7,>=3.13"), ["3.13", "3.14"]);
+        assert_eq!(matrix(">=3.13,!=4.0"), ["3.13", "3.14"]);
+    }
+
+    #[test]
+    fn matrix_compatible_release() {
+        assert_eq!(matrix("~=3.9.1"), ["3.9"]);
+        assert_eq!(matrix("~=3.12"), ["3.12", "3.13", "3.14"]);
+    }
+
+    #[test]
+    fn matrix_rejects_invalid_specifiers() {
+        assert!(python_matrix(">=4").is_err());
+        assert!(python_matrix("<2.7").is_err());
+        assert!(python_matrix("~=3").is_err());
+        assert!(python_matrix(">=3.12,<3.10").is_err());
+        assert!(python_matrix("latest").is_err());
+    }
+
+    #[test]
+    fn github_workflow_snapshot() {
+        assert_eq!(
+            CiProvider::Github.render(">=3.12,<4", "main").unwrap(),
+            include_str!("snapshots/ci_github.yml")
+        );
+    }
+
+    #[test]
+    fn github_workflow_snapshot_with_custom_branch() {
+        assert_eq!(
+            CiProvider::Github.render(">=3.12,<4", "develop").unwrap(),
+            include_str!("snapshots/ci_github_develop.yml")
+        );
+    }
+
+    #[test]
+    fn gitlab_workflow_snapshot() {
+        assert_eq!(
+            CiProvider::Gitlab.render(">=3.12,<4", "main").unwrap(),
+            include_str!("snapshots/ci_gitlab.yml")
+        );
+    }
+}
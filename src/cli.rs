@@ -0,0 +1,126 @@
+use crate::helpers::requirement_name;
+use crate::pyproject;
+use crate::template::{Template, TemplateFile};
+use std::fmt;
+use std::str::FromStr;
+use toml_edit::{value, Array, DocumentMut};
+
+/// Frameworks for the command-line interface generated by `hajime new --cli`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum CliFramework {
+    #[default]
+    Argparse,
+    Click,
+    Typer,
+}
+
+impl FromStr for CliFramework {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "argparse" => Ok(CliFramework::Argparse),
+            "click" => Ok(CliFramework::Click),
+            "typer" => Ok(CliFramework::Typer),
+            _ => Err(format!(
+                "Unknown CLI framework '{}' (expected argparse, click or typer)",
+                s
+            )),
+        }
+    }
+}
+
+impl fmt::Display for CliFramework {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            CliFramework::Argparse => "argparse",
+            CliFramework::Click => "click",
+            CliFramework::Typer => "typer",
+        })
+    }
+}
+
+impl CliFramework {
+    pub const ALL: [CliFramework; 3] = [
+        CliFramework::Argparse,
+        CliFramework::Click,
+        CliFramework::Typer,
+    ];
+
+    /// The `cli.py`, `__main__.py` and test files for this framework.
+    pub fn template(self) -> Template {
+        let (cli, main, test) = match self {
+            CliFramework::Argparse => (
+                include_str!("../templates/cli/argparse/cli.py"),
+                include_str!("../templates/cli/argparse/__main__.py"),
+                include_str!("../templates/cli/argparse/test_cli.py"),
+            ),
+            CliFramework::Click => (
+                include_str!("../templates/cli/click/cli.py"),
+                include_str!("../templates/cli/click/__main__.py"),
+                include_str!("../templates/cli/click/test_cli.py"),
+            ),
+            CliFramework::Typer => (
+                include_str!("../templates/cli/typer/cli.py"),
+                include_str!("../templates/cli/typer/__main__.py"),
+                include_str!("../templates/cli/typer/test_cli.py"),
+            ),
+        };
+        let files = [
+            ("{{package_dir}}/cli.py", cli),
+            ("{{package_dir}}/__main__.py", main),
+            ("tests/test_cli.py", test),
+        ];
+
+        Template {
+            name: format!("cli-{}", self),
+            files: files
+                .into_iter()
+                .map(|(path, contents)| TemplateFile {
+                    path: path.to_string(),
+                    contents: contents.as_bytes().to_vec(),
+                })
+                .collect(),
+        }
+    }
+
+    /// The runtime dependency the CLI needs, if any.
+    fn dependency(self) -> Option<&'static str> {
+        match self {
+            CliFramework::Argparse => None,
+            CliFramework::Click => Some("click>=8.0"),
+            CliFramework::Typer => Some("typer>=0.12"),
+        }
+    }
+
+    /// The object in `cli.py` that the console script calls.
+    fn entry_object(self) -> &'static str {
+        match self {
+            CliFramework::Argparse | CliFramework::Click => "main",
+            CliFramework::Typer => "app",
+        }
+    }
+
+    /// Registers the console script under `[project.scripts]` and adds the dependency.
+    pub fn configure(self, doc: &mut DocumentMut, project_name: &str, module_name: &str) {
+        let scripts = pyproject::table_mut(doc, &["project", "scripts"]);
+        scripts[project_name] = value(format!("{}.cli:{}", module_name, self.entry_object()));
+
+        if let Some(dependency) = self.dependency() {
+            let project = pyproject::table_mut(doc, &["project"]);
+            let mut dependencies = project
+                .get("dependencies")
+                .and_then(|d| d.as_array())
+                .cloned()
+                .unwrap_or_else(Array::new);
+            let name = requirement_name(dependency);
+            if !dependencies
+                .iter()
+                .any(|d| d.as_str().is_some_and(|d| requirement_name(d) == name))
+            {
+                dependencies.push(dependency);
+            }
+            project["dependencies"] = value(dependencies);
+        }
+    }
+}
@@ -0,0 +1,260 @@
+use crate::backend::Backend;
+use crate::gitignore::Preset;
+use crate::layout::Layout;
+use crate::license;
+use crate::new::validate_python_version;
+use crate::pyproject;
+use crate::template::Template;
+use std::env;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use toml_edit::DocumentMut;
+
+/// Settings that can be stored in the user configuration file or in
+/// `[tool.hajime]`, with what they are used for.
+pub const KEYS: &[(&str, &str)] = &[
+    ("account", "PyPI account used by `hajime publish`"),
+    ("author", "Author name for new projects"),
+    ("author-email", "Author email for new projects"),
+    ("backend", "Build backend for new projects"),
+    (
+        "index-url",
+        "Package index JSON API URL (overridden by --index-url and $HAJIME_INDEX_URL; default PyPI)",
+    ),
+    (
+        "gitignore",
+        "Comma-separated .gitignore presets for new projects, e.g. macos,data",
+    ),
+    ("layout", "Package layout for new projects (flat or src)"),
+    ("license", "SPDX license expression for new projects"),
+    ("python-version", "Minimum Python version for new projects"),
+    ("template", "Template for new projects"),
+];
+
+/// Where a configured value comes from.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Scope {
+    /// `[tool.hajime]` in the project's `pyproject.toml`.
+    Project,
+    /// The user configuration file.
+    User,
+}
+
+impl fmt::Display for Scope {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            Scope::Project => "project",
+            Scope::User => "user",
+        })
+    }
+}
+
+/// Path of the user configuration file: `$XDG_CONFIG_HOME/hajime/config.toml`,
+/// else `~/.config/hajime/config.toml`.
+pub fn user_config_path() -> Option<PathBuf> {
+    let config_home = env::var_os("XDG_CONFIG_HOME")
+        .filter(|d| !d.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))?;
+    Some(config_home.join("hajime").join("config.toml"))
+}
+
+/// Reads `path`, treating a missing file as empty.
+fn read_or_empty(path: &Path) -> io::Result<DocumentMut> {
+    if path.exists() {
+        pyproject::read(path)
+    } else {
+        Ok(DocumentMut::new())
+    }
+}
+
+/// The merged configuration: project settings take precedence over user settings.
+/// Command-line flags in turn take precedence over both.
+pub struct Config {
+    user: DocumentMut,
+    project: DocumentMut,
+}
+
+impl Config {
+    /// Loads the user configuration and the `pyproject.toml` of the project in `root`.
+    pub fn load(root: &Path) -> io::Result<Config> {
+        let user = match user_config_path() {
+            Some(path) => read_or_empty(&path)?,
+            None => DocumentMut::new(),
+        };
+        let project = read_or_empty(&root.join("pyproject.toml"))?;
+        Ok(Config { user, project })
+    }
+
+    /// The value of `key` and the scope it was found in.
+    pub fn lookup(&self, key: &str) -> Option<(String, Scope)> {
+        let project = self
+            .project
+            .get("tool")
+            .and_then(|t| t.get("hajime"))
+            .and_then(|h| h.get(key))
+            .and_then(|v| v.as_str());
+        if let Some(value) = project {
+            return Some((value.to_string(), Scope::Project));
+        }
+        self.user
+            .get(key)
+            .and_then(|v| v.as_str())
+            .map(|value| (value.to_string(), Scope::User))
+    }
+
+    /// The value of `key`, if configured.
+    pub fn get(&self, key: &str) -> Option<String> {
+        self.lookup(key).map(|(value, _)| value)
+    }
+
+    /// Parses `flag` if given, else the configured value of `key`.
+    pub fn resolve<T: FromStr<Err = String>>(
+        &self,
+        flag: Option<&str>,
+        key: &str,
+    ) -> io::Result<Option<T>> {
+        let Some(value) = flag.map(str::to_string).or_else(|| self.get(key)) else {
+            return Ok(None);
+        };
+        value.parse().map(Some).map_err(|e| {
+            io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("Invalid {}: {}", key, e),
+            )
+        })
+    }
+
+    /// Parses `flags` if any are given, else the comma-separated configured value
+    /// of `key`.
+    pub fn resolve_list<T: FromStr<Err = String>>(
+        &self,
+        flags: &[String],
+        key: &str,
+    ) -> io::Result<Vec<T>> {
+        let value = if flags.is_empty() {
+            match self.get(key) {
+                Some(value) => value,
+                None => return Ok(Vec::new()),
+            }
+        } else {
+            flags.join(",")
+        };
+        parse_list(&value).map_err(|e| {
+            io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("Invalid {}: {}", key, e),
+            )
+        })
+    }
+}
+
+/// Parses a comma-separated list such as `macos, data`.
+fn parse_list<T: FromStr<Err = String>>(value: &str) -> Result<Vec<T>, String> {
+    value.split(',').map(|item| item.trim().parse()).collect()
+}
+
+/// Checks that `key` is a known setting.
+fn check_key(key: &str) -> io::Result<()> {
+    if KEYS.iter().any(|(k, _)| *k == key) {
+        return Ok(());
+    }
+    let keys: Vec<&str> = KEYS.iter().map(|(k, _)| *k).collect();
+    Err(io::Error::new(
+        io::ErrorKind::InvalidInput,
+        format!(
+            "Unknown setting '{}'. Known settings: {}.",
+            key,
+            keys.join(", ")
+        ),
+    ))
+}
+
+/// Checks that `key` is a known setting and `value` is valid for it.
+fn validate(key: &str, value: &str) -> io::Result<()> {
+    check_key(key)?;
+    let invalid = |e: String| io::Error::new(io::ErrorKind::InvalidInput, e);
+    match key {
+        "backend" => value.parse::<Backend>().map(|_| ()).map_err(invalid),
+        "gitignore" => parse_list::<Preset>(value).map(|_| ()).map_err(invalid),
+        "layout" => value.parse::<Layout>().map(|_| ()).map_err(invalid),
+        "python-version" => validate_python_version(value),
+        "license" => license::validate_expression(value),
+        "template" => Template::resolve(value).map(|_| ()),
+        _ => Ok(()),
+    }
+}
+
+/// Prints the effective value of `key` for the project in `root`.
+pub fn get(root: &Path, key: &str) -> io::Result<()> {
+    check_key(key)?;
+    match Config::load(root)?.get(key) {
+        Some(value) => {
+            println!("{}", value);
+            Ok(())
+        }
+        None => Err(io::Error::new(
+            io::ErrorKind::NotFound,
+            format!("'{}' is not set.", key),
+        )),
+    }
+}
+
+/// Stores `key = value` in the user configuration file, or with `project` in
+/// `[tool.hajime]` of the project in `root`.
+pub fn set(root: &Path, key: &str, value: &str, project: bool) -> io::Result<()> {
+    validate(key, value)?;
+
+    let path = if project {
+        let path = root.join("pyproject.toml");
+        if !path.exists() {
+            return Err(io::Error::new(
+                io::ErrorKind::NotFound,
+                format!("pyproject.toml not found in {}.", root.display()),
+            ));
+        }
+        path
+    } else {
+        user_config_path().ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::NotFound,
+                "Cannot locate the user configuration directory; set XDG_CONFIG_HOME or HOME.",
+            )
+        })?
+    };
+
+    let mut doc = read_or_empty(&path)?;
+    if project {
+        pyproject::table_mut(&mut doc, &["tool", "hajime"])[key] = toml_edit::value(value);
+    } else {
+        doc[key] = toml_edit::value(value);
+    }
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    pyproject::write(&path, &doc)?;
+    println!("Set {} = \"{}\" in {}.", key, value, path.display());
+    Ok(())
+}
+
+/// Prints every configured setting with the scope it comes from.
+pub fn list(root: &Path) -> io::Result<()> {
+    let config = Config::load(root)?;
+    let mut any = false;
+    for (key, description) in KEYS {
+        if let Some((value, scope)) = config.lookup(key) {
+            println!("{} = \"{}\"  # {} ({})", key, value, description, scope);
+            any = true;
+        }
+    }
+    if !any {
+        match user_config_path() {
+            Some(path) => println!("No settings configured (user file: {}).", path.display()),
+            None => println!("No settings configured."),
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,93 @@
+use std::io;
+use std::path::Path;
+use std::process::Command;
+
+/// Reads a value from the user's git configuration, e.g. `user.name`.
+/// Returns `None` if git is unavailable or the key is unset.
+pub fn config_value(key: &str, dir: &Path) -> Option<String> {
+    let output = Command::new("git")
+        .args(["config", "--get", key])
+        .current_dir(dir)
+        .output()
+        .ok()?;
+
+    if !output.status.success() {
+        return None;
+    }
+
+    let value = String::from_utf8(output.stdout).ok()?.trim().to_string();
+    (!value.is_empty()).then_some(value)
+}
+
+/// Checks whether the repository at `dir` has uncommitted or untracked changes.
+pub fn has_uncommitted_changes(dir: &Path) -> io::Result<bool> {
+    let output = Command::new("git")
+        .args(["status", "--porcelain"])
+        .current_dir(dir)
+        .output()?;
+
+    if !output.status.success() {
+        return Err(io::Error::other(format!(
+            "git status failed in {}: {}",
+            dir.display(),
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+
+    Ok(!output.stdout.is_empty())
+}
+
+/// Runs git with `args` in `dir` and returns its trimmed standard output.
+/// A failing command is reported with git's error message.
+pub fn run(dir: &Path, args: &[&str]) -> io::Result<String> {
+    let output = Command::new("git").args(args).current_dir(dir).output()?;
+    if !output.status.success() {
+        return Err(io::Error::other(format!(
+            "git {} failed: {}",
+            args.join(" "),
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// Checks whether `dir` is inside a Git work tree.
+pub fn is_inside_work_tree(dir: &Path) -> bool {
+    run(dir, &["rev-parse", "--is-inside-work-tree"]).is_ok_and(|out| out == "true")
+}
+
+/// The branch the repository containing `dir` is on or, outside a repository,
+/// the branch `git init` would create.
+pub fn default_branch(dir: &Path) -> String {
+    run(dir, &["symbolic-ref", "--short", "HEAD"])
+        .ok()
+        .or_else(|| config_value("init.defaultBranch", dir))
+        // Git's built-in default when init.defaultBranch is unset
+        .unwrap_or_else(|| "master".to_string())
+}
+
+/// Initializes a repository in `dir`, optionally with a custom default branch.
+pub fn init(dir: &Path, branch: Option<&str>) -> io::Result<()> {
+    run(dir, &["init", "--quiet"])?;
+    if let Some(branch) = branch {
+        // Works on every git version, unlike `git init --initial-branch`
+        run(
+            dir,
+            &["symbolic-ref", "HEAD", &format!("refs/heads/{}", branch)],
+        )?;
+    }
+    Ok(())
+}
+
+/// Stages everything in `dir` and commits it with `message`, skipping hooks.
+pub fn commit_all(dir: &Path, message: &str) -> io::Result<()> {
+    run(dir, &["add", "--all"])?;
+    run(dir, &["commit", "--quiet", "--no-verify", "-m", message])?;
+    Ok(())
+}
+
+/// Adds a remote called `name` pointing at `url`.
+pub fn add_remote(dir: &Path, name: &str, url: &str) -> io::Result<()> {
+    run(dir, &["remote", "add", name, url])?;
+    Ok(())
+}
@@ -0,0 +1,142 @@
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::str::FromStr;
+
+/// File name of the ignore file.
+pub const GITIGNORE: &str = ".gitignore";
+
+/// Building blocks of a `.gitignore`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Preset {
+    Python,
+    Rust,
+    Jupyter,
+    Macos,
+    Editors,
+    Data,
+}
+
+impl FromStr for Preset {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "python" => Ok(Preset::Python),
+            "rust" => Ok(Preset::Rust),
+            "jupyter" => Ok(Preset::Jupyter),
+            "macos" => Ok(Preset::Macos),
+            "editors" => Ok(Preset::Editors),
+            "data" => Ok(Preset::Data),
+            _ => Err(format!(
+                "Unknown .gitignore preset '{}' (expected python, rust, jupyter, macos, editors or data)",
+                s
+            )),
+        }
+    }
+}
+
+impl fmt::Display for Preset {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            Preset::Python => "python",
+            Preset::Rust => "rust",
+            Preset::Jupyter => "jupyter",
+            Preset::Macos => "macos",
+            Preset::Editors => "editors",
+            Preset::Data => "data",
+        })
+    }
+}
+
+impl Preset {
+    /// Presets used by `hajime new` and `hajime init` unless others are chosen.
+    pub const DEFAULT: [Preset; 4] = [
+        Preset::Python,
+        Preset::Jupyter,
+        Preset::Macos,
+        Preset::Editors,
+    ];
+
+    fn contents(self) -> &'static str {
+        match self {
+            Preset::Python => include_str!("../templates/gitignore/python.gitignore"),
+            Preset::Rust => include_str!("../templates/gitignore/rust.gitignore"),
+            Preset::Jupyter => include_str!("../templates/gitignore/jupyter.gitignore"),
+            Preset::Macos => include_str!("../templates/gitignore/macos.gitignore"),
+            Preset::Editors => include_str!("../templates/gitignore/editors.gitignore"),
+            Preset::Data => include_str!("../templates/gitignore/data.gitignore"),
+        }
+    }
+}
+
+/// Appends the patterns of `presets` that `existing` does not contain yet.
+/// Each block of a preset keeps its comment header, but only if it adds a pattern.
+/// Returns the merged contents and the number of patterns added.
+pub fn merge(existing: &str, presets: &[Preset]) -> (String, usize) {
+    let mut merged = existing.to_string();
+    let mut added = 0;
+
+    for preset in presets {
+        for block in preset.contents().split("\n\n") {
+            let (comments, patterns): (Vec<&str>, Vec<&str>) = block
+                .lines()
+                .filter(|line| !line.trim().is_empty())
+                .partition(|line| line.starts_with('#'));
+            let new: Vec<&str> = patterns
+                .into_iter()
+                .filter(|pattern| !merged.lines().any(|line| line.trim() == pattern.trim()))
+                .collect();
+            if new.is_empty() {
+                continue;
+            }
+
+            if !merged.is_empty() {
+                if !merged.ends_with('\n') {
+                    merged.push('\n');
+                }
+                merged.push('\n');
+            }
+            for line in comments.iter().chain(&new) {
+                merged.push_str(line);
+                merged.push('\n');
+            }
+            added += new.len();
+        }
+    }
+
+    (merged, added)
+}
+
+/// Writes or extends the `.gitignore` in `dir` with `presets`.
+/// Returns the number of patterns added.
+pub fn write(dir: &Path, presets: &[Preset]) -> io::Result<usize> {
+    let path = dir.join(GITIGNORE);
+    let existing = if path.exists() {
+        fs::read_to_string(&path)?
+    } else {
+        String::new()
+    };
+    let (merged, added) = merge(&existing, presets);
+    if added > 0 || !path.exists() {
+        fs::write(&path, merged)?;
+    }
+    Ok(added)
+}
+
+/// Merges `presets` into the `.gitignore` of the project in `root`.
+pub fn add(root: &Path, presets: &[Preset]) -> io::Result<()> {
+    let added = write(root, presets)?;
+    let names: Vec<String> = presets.iter().map(Preset::to_string).collect();
+    if added == 0 {
+        println!(".gitignore already covers {}.", names.join(", "));
+    } else {
+        println!(
+            "Added {} patterns for {} to .gitignore.",
+            added,
+            names.join(", ")
+        );
+    }
+    Ok(())
+}
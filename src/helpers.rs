@@ -1,6 +1,8 @@
 use std::fs;
+use std::io::{self, Write};
 use std::path::{Path, PathBuf};
 use std::process::Command;
+use std::time::{SystemTime, UNIX_EPOCH};
 
 // Helper function to check if `python` is available
 pub fn is_python_available() -> bool {
@@ -56,11 +58,11 @@ pub fn get_python_info() -> Option<(String, String)> {
     Some((python_path, python_version))
 }
 
-pub fn get_latest_wheel_file(directory: &str) -> Option<PathBuf> {
+pub fn get_latest_wheel_file(directory: &Path) -> Option<PathBuf> {
     let mut files: Vec<_> = fs::read_dir(directory)
         .ok()?
         .filter_map(|entry| entry.ok())
-        .filter(|entry| entry.path().extension().map_or(false, |ext| ext == "whl"))
+        .filter(|entry| entry.path().extension().is_some_and(|ext| ext == "whl"))
         .collect();
 
     files.sort_by_key(|entry| entry.metadata().and_then(|meta| meta.modified()).ok());
@@ -85,12 +87,142 @@ pub fn is_git_installed() -> bool {
         .unwrap_or(false)
 }
 
-/// Detects if the project is a Rust-based Python project.
-pub fn is_rust_python_project() -> bool {
-    Path::new("Cargo.toml").exists() && Path::new("target/wheels").exists()
+/// The project root for `start`: the nearest directory at or above it that holds a
+/// `pyproject.toml` or `Cargo.toml`, or `start` itself if there is none.
+pub fn find_project_root(start: &Path) -> PathBuf {
+    let is_root =
+        |dir: &Path| dir.join("pyproject.toml").exists() || dir.join("Cargo.toml").exists();
+    if is_root(start) {
+        return start.to_path_buf();
+    }
+    start
+        .canonicalize()
+        .ok()
+        .and_then(|dir| dir.ancestors().find(|d| is_root(d)).map(Path::to_path_buf))
+        .unwrap_or_else(|| start.to_path_buf())
+}
+
+/// Detects if the project in `dir` is a Rust-based Python project.
+pub fn is_rust_python_project(dir: &Path) -> bool {
+    dir.join("Cargo.toml").exists() && dir.join("target/wheels").exists()
+}
+
+/// The distribution name at the start of a requirement specifier, lowercased,
+/// e.g. `click` in `click>=8.0`.
+pub fn requirement_name(requirement: &str) -> String {
+    requirement
+        .trim()
+        .split(|c: char| !(c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.')))
+        .next()
+        .unwrap_or_default()
+        .to_ascii_lowercase()
+}
+
+/// Reads the requirement specifiers from a `requirements.txt` file.
+/// Comments, blank lines and pip options (`-r`, `-e`, `--index-url`, ...) are skipped.
+pub fn read_requirements(path: &Path) -> io::Result<Vec<String>> {
+    let content = fs::read_to_string(path)?;
+    Ok(content
+        .lines()
+        .map(|line| match line.find(" #") {
+            Some(index) => &line[..index],
+            None => line,
+        })
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#') && !line.starts_with('-'))
+        .map(str::to_string)
+        .collect())
+}
+
+/// Asks a yes/no question on the terminal. Anything but `y`/`yes` means no.
+pub fn confirm(prompt: &str) -> io::Result<bool> {
+    print!("{} [y/N] ", prompt);
+    io::stdout().flush()?;
+
+    let mut answer = String::new();
+    io::stdin().read_line(&mut answer)?;
+    Ok(matches!(answer.trim().to_lowercase().as_str(), "y" | "yes"))
+}
+
+/// Asks for a value on the terminal, returning `default` for an empty answer.
+pub fn ask(prompt: &str, default: &str) -> io::Result<String> {
+    if default.is_empty() {
+        print!("{}: ", prompt);
+    } else {
+        print!("{} [{}]: ", prompt, default);
+    }
+    io::stdout().flush()?;
+
+    let mut answer = String::new();
+    io::stdin().read_line(&mut answer)?;
+    let answer = answer.trim();
+    Ok(if answer.is_empty() {
+        default.to_string()
+    } else {
+        answer.to_string()
+    })
+}
+
+/// Asks until the answer is one of `choices`.
+pub fn ask_choice(prompt: &str, choices: &[&str], default: &str) -> io::Result<String> {
+    let prompt = format!("{} ({})", prompt, choices.join("/"));
+    loop {
+        let answer = ask(&prompt, default)?;
+        if choices.contains(&answer.as_str()) {
+            return Ok(answer);
+        }
+        println!("Please answer one of: {}", choices.join(", "));
+    }
+}
+
+/// Asks a yes/no question with a default answer.
+pub fn ask_yes_no(prompt: &str, default: bool) -> io::Result<bool> {
+    let choices = if default { "Y/n" } else { "y/N" };
+    loop {
+        let answer = ask(&format!("{} [{}]", prompt, choices), "")?;
+        match answer.to_lowercase().as_str() {
+            "" => return Ok(default),
+            "y" | "yes" => return Ok(true),
+            "n" | "no" => return Ok(false),
+            _ => println!("Please answer y or n."),
+        }
+    }
+}
+
+/// Returns the current UTC date and time as `(year, month, day, hour, minute, second)`.
+pub fn utc_now() -> (i64, u32, u32, u32, u32, u32) {
+    let secs = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or(0);
+    let (days, rem) = (secs.div_euclid(86_400), secs.rem_euclid(86_400));
+
+    // Civil date from days since 1970-01-01 (Howard Hinnant's algorithm)
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let year = yoe + era * 400 + i64::from(month <= 2);
+
+    (
+        year,
+        month,
+        day,
+        (rem / 3_600) as u32,
+        (rem % 3_600 / 60) as u32,
+        (rem % 60) as u32,
+    )
 }
 
-/// Converts kebab-case to snake_case
-pub fn to_snake_case(name: &str) -> String {
-    name.replace('-', "_")
+/// Returns a `YYYYMMDD-HHMMSS` UTC timestamp for file names.
+pub fn timestamp() -> String {
+    let (year, month, day, hour, minute, second) = utc_now();
+    format!(
+        "{:04}{:02}{:02}-{:02}{:02}{:02}",
+        year, month, day, hour, minute, second
+    )
 }
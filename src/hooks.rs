@@ -0,0 +1,147 @@
+use crate::pyproject;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Configuration for the `pre-commit` tool, written by `hajime new --pre-commit`.
+pub const PRE_COMMIT_CONFIG: &str = ".pre-commit-config.yaml";
+
+/// Commands the native hook runs when `[tool.hajime.hooks] pre-commit` is not set.
+const DEFAULT_COMMANDS: &[&str] = &["ruff check .", "ruff format --check ."];
+
+/// First line after the shebang of hooks written by `hajime`, used to recognize them.
+const HOOK_MARKER: &str = "# Installed by hajime";
+
+/// Writes `.pre-commit-config.yaml` to `dir`.
+pub fn write_pre_commit_config(dir: &Path) -> io::Result<()> {
+    fs::write(
+        dir.join(PRE_COMMIT_CONFIG),
+        include_str!("../templates/pre-commit-config.yaml"),
+    )
+}
+
+/// The commands listed in `[tool.hajime.hooks] pre-commit`, or the defaults.
+fn pre_commit_commands(dir: &Path) -> io::Result<Vec<String>> {
+    let path = dir.join("pyproject.toml");
+    if !path.exists() {
+        return Ok(DEFAULT_COMMANDS.iter().map(|c| c.to_string()).collect());
+    }
+
+    let doc = pyproject::read(&path)?;
+    let configured = doc
+        .get("tool")
+        .and_then(|t| t.get("hajime"))
+        .and_then(|h| h.get("hooks"))
+        .and_then(|h| h.get("pre-commit"));
+    match configured {
+        None => Ok(DEFAULT_COMMANDS.iter().map(|c| c.to_string()).collect()),
+        Some(item) => item
+            .as_array()
+            .and_then(|a| a.iter().map(|c| c.as_str().map(str::to_string)).collect())
+            .ok_or_else(|| {
+                io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    "[tool.hajime.hooks] pre-commit must be an array of command strings.",
+                )
+            }),
+    }
+}
+
+/// Path of the pre-commit hook of the repository in `dir`, honoring `core.hooksPath`.
+fn hook_path(dir: &Path) -> io::Result<PathBuf> {
+    let output = Command::new("git")
+        .args(["rev-parse", "--git-path", "hooks/pre-commit"])
+        .current_dir(dir)
+        .output()?;
+    if !output.status.success() {
+        return Err(io::Error::new(
+            io::ErrorKind::NotFound,
+            "Not a Git repository; run `git init` first.",
+        ));
+    }
+    let path = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    Ok(dir.join(path))
+}
+
+/// Path of `dir` relative to the top level of its repository, e.g. `demo/` for a
+/// project nested in a larger repository; empty at the top level.
+fn repository_prefix(dir: &Path) -> io::Result<String> {
+    let output = Command::new("git")
+        .args(["rev-parse", "--show-prefix"])
+        .current_dir(dir)
+        .output()?;
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// Renders the hook script running `commands` in the project directory `prefix`
+/// with the project's (or the repository's) `.venv` on `PATH`.
+/// The script stops with a hint when a program the commands need is missing.
+fn hook_script(commands: &[String], prefix: &str) -> String {
+    let mut script = format!(
+        "#!/bin/sh\n{}; change [tool.hajime.hooks] pre-commit in pyproject.toml and run `hajime hooks install --force` to update.\nset -e\ntop=\"$(git rev-parse --show-toplevel)\"\ncd \"$top/{}\"\nPATH=\"$PWD/.venv/bin:$top/.venv/bin:$PATH\"\n\n",
+        HOOK_MARKER, prefix
+    );
+    let mut programs: Vec<&str> = Vec::new();
+    for program in commands.iter().filter_map(|c| c.split_whitespace().next()) {
+        if !programs.contains(&program) {
+            programs.push(program);
+        }
+    }
+    for program in &programs {
+        script.push_str(&format!(
+            "command -v {0} >/dev/null 2>&1 || {{ echo \"pre-commit: {0} not found. Run 'uv sync' to create .venv or install {0}; 'git commit --no-verify' skips this hook.\" >&2; exit 1; }}\n",
+            program
+        ));
+    }
+    if !programs.is_empty() {
+        script.push('\n');
+    }
+    for command in commands {
+        script.push_str(command);
+        script.push('\n');
+    }
+    script
+}
+
+/// Installs a native git pre-commit hook for the repository in `dir`.
+/// A hook not written by `hajime` is only replaced with `force`.
+pub fn install_hook(dir: &Path, force: bool) -> io::Result<PathBuf> {
+    let path = hook_path(dir)?;
+    if let Ok(existing) = fs::read_to_string(&path) {
+        if !existing.contains(HOOK_MARKER) && !force {
+            return Err(io::Error::new(
+                io::ErrorKind::AlreadyExists,
+                format!(
+                    "{} already exists and was not installed by hajime; use --force to replace it.",
+                    path.strip_prefix(dir).unwrap_or(&path).display()
+                ),
+            ));
+        }
+    }
+
+    let commands = pre_commit_commands(dir)?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(&path, hook_script(&commands, &repository_prefix(dir)?))?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o755))?;
+    }
+    Ok(path)
+}
+
+/// Installs the pre-commit hook of the project in `dir`.
+pub fn install(dir: &Path, force: bool) -> io::Result<()> {
+    let path = install_hook(dir, force)?;
+    println!(
+        "Installed the pre-commit hook at {} running:",
+        path.strip_prefix(dir).unwrap_or(&path).display()
+    );
+    for command in pre_commit_commands(dir)? {
+        println!("    {}", command);
+    }
+    Ok(())
+}
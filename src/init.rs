@@ -0,0 +1,202 @@
+use crate::gitignore::{self, GITIGNORE};
+use crate::helpers::read_requirements;
+use crate::layout::Layout;
+use crate::naming;
+use crate::new::{configure_pyproject, init_git, setup_venv, template_context, NewOptions};
+use crate::pyproject::{self, string_array};
+use crate::template::{Template, DEFAULT_TEMPLATE};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use toml_edit::value;
+
+/// Top-level directories that never hold the project's import package.
+const NON_PACKAGE_DIRS: &[&str] = &["tests", "test", "docs", "build", "dist", "venv", "env"];
+
+/// What `hajime init` found in the directory before adding anything.
+struct Existing {
+    /// Import name and layout of an existing package directory.
+    package: Option<(String, Layout)>,
+    pyproject: bool,
+    setup_py: bool,
+    setup_cfg: bool,
+    requirements: bool,
+    tests: bool,
+    git: bool,
+    venv: bool,
+}
+
+impl Existing {
+    fn detect(dir: &Path, module_name: &str) -> Existing {
+        Existing {
+            package: find_package(dir, module_name),
+            pyproject: dir.join("pyproject.toml").exists(),
+            setup_py: dir.join("setup.py").exists(),
+            setup_cfg: dir.join("setup.cfg").exists(),
+            requirements: dir.join("requirements.txt").exists(),
+            tests: dir.join("tests").exists(),
+            git: dir.join(".git").exists(),
+            venv: dir.join(".venv").exists(),
+        }
+    }
+}
+
+/// Turns a directory into a Python project in place.
+/// Only missing pieces are added; existing files are never deleted or overwritten.
+///
+/// # Arguments
+/// * `base_path` - The directory to turn into a project.
+/// * `name` - The project name (defaults to the directory name).
+/// * `options` - Template, layout (used when no package directory exists yet),
+///   backend and metadata for the files that are added.
+pub fn init_project(base_path: &Path, name: Option<&str>, options: &NewOptions) -> io::Result<()> {
+    let dir_name = base_path
+        .canonicalize()?
+        .file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "Cannot infer a project name; pass --name.",
+            )
+        })?;
+    let project_name = name.unwrap_or(&dir_name);
+    let template = Template::resolve(options.template.as_deref().unwrap_or(DEFAULT_TEMPLATE))?;
+
+    naming::validate_project_name(project_name)?;
+    let derived_name = naming::import_name(project_name, options.import_name.as_deref())?;
+    let existing = Existing::detect(base_path, &derived_name);
+    let (module_name, layout) = match &existing.package {
+        Some((module_name, layout)) => {
+            println!(
+                "Found package '{}' in {}.",
+                module_name,
+                layout.package_dir(module_name)
+            );
+            (module_name.clone(), *layout)
+        }
+        None => (derived_name, options.layout),
+    };
+    let options = NewOptions {
+        layout,
+        ..options.clone()
+    };
+    let package_dir = PathBuf::from(layout.package_dir(&module_name));
+
+    // Render only the pieces that are missing
+    let context = template_context(base_path, project_name, &module_name, &options);
+    let written = template.render_filtered(&context, base_path, |path| {
+        if base_path.join(path).exists() {
+            return false;
+        }
+        if existing.package.is_some() && path.starts_with(&package_dir) {
+            return false;
+        }
+        // Template tests exercise template code, which an existing package does not have
+        !(path.starts_with("tests") && (existing.tests || existing.package.is_some()))
+    })?;
+    for path in &written {
+        println!(
+            "Created {}",
+            path.strip_prefix(base_path).unwrap_or(path).display()
+        );
+    }
+
+    if !base_path.join(GITIGNORE).exists() {
+        gitignore::write(base_path, &options.gitignore_presets())?;
+        println!("Created {}", GITIGNORE);
+    }
+
+    if existing.package.is_some() && !existing.tests {
+        let tests_dir = base_path.join("tests");
+        fs::create_dir_all(&tests_dir)?;
+        fs::write(tests_dir.join("__init__.py"), "")?;
+        fs::write(
+            tests_dir.join("test_import.py"),
+            format!(
+                "import {module_name}\n\n\ndef test_import():\n    assert {module_name} is not None\n"
+            ),
+        )?;
+        println!("Created tests/test_import.py");
+    }
+
+    let pyproject_path = base_path.join("pyproject.toml");
+    if existing.pyproject {
+        println!("Keeping existing pyproject.toml.");
+    } else if pyproject_path.exists() {
+        configure_pyproject(&pyproject_path, project_name, &module_name, &options)?;
+        if existing.requirements {
+            let requirements = read_requirements(&base_path.join("requirements.txt"))?;
+            let mut doc = pyproject::read(&pyproject_path)?;
+            doc["project"]["dependencies"] = value(string_array(&requirements));
+            pyproject::write(&pyproject_path, &doc)?;
+            println!(
+                "Added {} dependencies from requirements.txt.",
+                requirements.len()
+            );
+        }
+    }
+
+    if existing.setup_py || existing.setup_cfg {
+        println!(
+            "Note: setup.py/setup.cfg found; run `hajime migrate` to copy their metadata into pyproject.toml."
+        );
+    }
+
+    if existing.git {
+        println!("Keeping existing Git repository.");
+    } else {
+        init_git(base_path, &options)?;
+    }
+
+    if existing.venv {
+        println!("Keeping existing virtual environment `.venv`.");
+    } else {
+        setup_venv(base_path, project_name);
+    }
+
+    println!("\nProject '{project_name}' initialized successfully!");
+    Ok(())
+}
+
+/// Looks for the project's import package, preferring one named `module_name`.
+fn find_package(dir: &Path, module_name: &str) -> Option<(String, Layout)> {
+    if dir.join(module_name).join("__init__.py").exists() {
+        return Some((module_name.to_string(), Layout::Flat));
+    }
+    if dir
+        .join("src")
+        .join(module_name)
+        .join("__init__.py")
+        .exists()
+    {
+        return Some((module_name.to_string(), Layout::Src));
+    }
+
+    package_dirs(dir)
+        .into_iter()
+        .next()
+        .map(|name| (name, Layout::Flat))
+        .or_else(|| {
+            package_dirs(&dir.join("src"))
+                .into_iter()
+                .next()
+                .map(|name| (name, Layout::Src))
+        })
+}
+
+/// Names of the directories directly below `dir` that contain an `__init__.py`.
+fn package_dirs(dir: &Path) -> Vec<String> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+
+    let mut names: Vec<String> = entries
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.path().join("__init__.py").exists())
+        .map(|entry| entry.file_name().to_string_lossy().into_owned())
+        .filter(|name| !name.starts_with('.') && !NON_PACKAGE_DIRS.contains(&name.as_str()))
+        .collect();
+    names.sort();
+    names
+}
@@ -0,0 +1,52 @@
+use std::fmt;
+use std::str::FromStr;
+
+/// Where the import package lives inside a project.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Layout {
+    /// `<project>/<module>/`
+    #[default]
+    Flat,
+    /// `<project>/src/<module>/`
+    Src,
+}
+
+impl FromStr for Layout {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "flat" => Ok(Layout::Flat),
+            "src" => Ok(Layout::Src),
+            _ => Err(format!("Unknown layout '{}' (expected flat or src)", s)),
+        }
+    }
+}
+
+impl fmt::Display for Layout {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            Layout::Flat => "flat",
+            Layout::Src => "src",
+        })
+    }
+}
+
+impl Layout {
+    /// Path of the package directory relative to the project root.
+    pub fn package_dir(self, module_name: &str) -> String {
+        match self {
+            Layout::Flat => module_name.to_string(),
+            Layout::Src => format!("src/{}", module_name),
+        }
+    }
+
+    /// Extra pytest options the layout needs.
+    pub fn pytest_addopts(self) -> &'static [&'static str] {
+        match self {
+            Layout::Flat => &[],
+            // Import the installed package rather than the source tree under test.
+            Layout::Src => &["--import-mode=importlib"],
+        }
+    }
+}
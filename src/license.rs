@@ -0,0 +1,314 @@
+use crate::git;
+use crate::helpers::utc_now;
+use crate::pyproject::{self, string_array};
+use crate::template::TemplateContext;
+use std::fs;
+use std::io;
+use std::path::Path;
+use toml_edit::{value, Array, DocumentMut};
+
+/// File name the license text is written to.
+pub const LICENSE_FILE: &str = "LICENSE";
+
+/// A license whose full text `hajime` can write.
+pub struct License {
+    /// SPDX identifier, e.g. `MIT`.
+    pub id: &'static str,
+    text: &'static str,
+}
+
+const LICENSES: &[License] = &[
+    License {
+        id: "MIT",
+        text: include_str!("../templates/licenses/MIT.txt"),
+    },
+    License {
+        id: "Apache-2.0",
+        text: include_str!("../templates/licenses/Apache-2.0.txt"),
+    },
+    License {
+        id: "BSD-2-Clause",
+        text: include_str!("../templates/licenses/BSD-2-Clause.txt"),
+    },
+    License {
+        id: "BSD-3-Clause",
+        text: include_str!("../templates/licenses/BSD-3-Clause.txt"),
+    },
+    License {
+        id: "ISC",
+        text: include_str!("../templates/licenses/ISC.txt"),
+    },
+    License {
+        id: "GPL-2.0-only",
+        text: include_str!("../templates/licenses/GPL-2.0.txt"),
+    },
+    License {
+        id: "GPL-2.0-or-later",
+        text: include_str!("../templates/licenses/GPL-2.0.txt"),
+    },
+    License {
+        id: "GPL-3.0-only",
+        text: include_str!("../templates/licenses/GPL-3.0.txt"),
+    },
+    License {
+        id: "GPL-3.0-or-later",
+        text: include_str!("../templates/licenses/GPL-3.0.txt"),
+    },
+    License {
+        id: "LGPL-2.1-only",
+        text: include_str!("../templates/licenses/LGPL-2.1.txt"),
+    },
+    License {
+        id: "LGPL-2.1-or-later",
+        text: include_str!("../templates/licenses/LGPL-2.1.txt"),
+    },
+    License {
+        id: "LGPL-3.0-only",
+        text: include_str!("../templates/licenses/LGPL-3.0.txt"),
+    },
+    License {
+        id: "LGPL-3.0-or-later",
+        text: include_str!("../templates/licenses/LGPL-3.0.txt"),
+    },
+    License {
+        id: "MPL-2.0",
+        text: include_str!("../templates/licenses/MPL-2.0.txt"),
+    },
+    License {
+        id: "Unlicense",
+        text: include_str!("../templates/licenses/Unlicense.txt"),
+    },
+];
+
+impl License {
+    /// Looks up a license by its SPDX identifier, ignoring case.
+    pub fn find(id: &str) -> Option<&'static License> {
+        LICENSES.iter().find(|l| l.id.eq_ignore_ascii_case(id))
+    }
+
+    /// The full license text with the current year and `author` filled in.
+    pub fn text(&self, author: &str) -> String {
+        let mut context = TemplateContext::default();
+        context.set("year", &utc_now().0.to_string());
+        context.set("author", author);
+        context.render(self.text)
+    }
+
+    /// Writes the license text to `LICENSE` in `dir`.
+    pub fn write_file(&self, dir: &Path, author: &str) -> io::Result<()> {
+        fs::write(dir.join(LICENSE_FILE), self.text(author))
+    }
+}
+
+/// Comma-separated identifiers of the licenses with a bundled text.
+pub fn known_ids() -> String {
+    LICENSES.iter().map(|l| l.id).collect::<Vec<_>>().join(", ")
+}
+
+/// Whether `classifier` is a `License ::` trove classifier. PEP 639 deprecates these
+/// next to a `license` expression and setuptools rejects the combination, so they are
+/// dropped wherever `license` is written as an SPDX expression.
+pub fn is_license_classifier(classifier: &str) -> bool {
+    classifier.starts_with("License ::")
+}
+
+/// Whether `s` can be a license or exception identifier such as `MIT`,
+/// `GPL-3.0-or-later` or `LicenseRef-Proprietary`.
+fn is_identifier(s: &str) -> bool {
+    !s.is_empty()
+        && !["AND", "OR", "WITH"].contains(&s.to_ascii_uppercase().as_str())
+        && s.chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '.' || c == '-')
+}
+
+/// Checks that `expression` is a syntactically valid SPDX license expression,
+/// e.g. `MIT`, `Apache-2.0 OR MIT` or `(GPL-2.0-or-later WITH Classpath-exception-2.0)`.
+pub fn validate_expression(expression: &str) -> io::Result<()> {
+    let spaced = expression.replace('(', " ( ").replace(')', " ) ");
+    let tokens: Vec<&str> = spaced.split_whitespace().collect();
+
+    // expression := term (("AND" | "OR") term)*
+    // term       := "(" expression ")" | id ["+"] ["WITH" id]
+    let mut depth = 0usize;
+    let mut expect_term = true;
+    let mut valid = !tokens.is_empty();
+    let mut i = 0;
+    while valid && i < tokens.len() {
+        let token = tokens[i];
+        let upper = token.to_ascii_uppercase();
+        if expect_term {
+            if token == "(" {
+                depth += 1;
+            } else {
+                let id = token.strip_suffix('+').unwrap_or(token);
+                valid = is_identifier(id);
+                if tokens
+                    .get(i + 1)
+                    .is_some_and(|t| t.eq_ignore_ascii_case("WITH"))
+                {
+                    valid = valid && tokens.get(i + 2).is_some_and(|t| is_identifier(t));
+                    i += 2;
+                }
+                expect_term = false;
+            }
+        } else if token == ")" {
+            valid = depth > 0;
+            depth = depth.saturating_sub(1);
+        } else {
+            valid = upper == "AND" || upper == "OR";
+            expect_term = true;
+        }
+        i += 1;
+    }
+
+    if valid && !expect_term && depth == 0 {
+        return Ok(());
+    }
+    Err(io::Error::new(
+        io::ErrorKind::InvalidInput,
+        format!(
+            "'{}' is not a valid SPDX license expression, e.g. MIT or Apache-2.0 OR MIT.",
+            expression
+        ),
+    ))
+}
+
+/// Sets the PEP 639 `license` and `license-files` fields and removes `License ::`
+/// classifiers. Errors if `id` is not a valid SPDX license expression.
+pub fn apply(doc: &mut DocumentMut, id: &str, with_file: bool) -> io::Result<()> {
+    validate_expression(id)?;
+    let license = License::find(id);
+    let project = pyproject::table_mut(doc, &["project"]);
+    project["license"] = value(license.map_or(id, |l| l.id));
+    if with_file {
+        project["license-files"] = value(string_array(&[LICENSE_FILE]));
+    }
+
+    let classifiers: Array = project
+        .get("classifiers")
+        .and_then(|c| c.as_array())
+        .map(|a| {
+            a.iter()
+                .filter(|c| !c.as_str().is_some_and(is_license_classifier))
+                .cloned()
+                .collect()
+        })
+        .unwrap_or_default();
+    if classifiers.is_empty() {
+        project.remove("classifiers");
+    } else {
+        project["classifiers"] = value(classifiers);
+    }
+    Ok(())
+}
+
+/// Sets the license of the project in `dir`: writes `LICENSE` and updates
+/// `pyproject.toml`.
+///
+/// # Arguments
+/// * `dir` - The project root.
+/// * `id` - SPDX identifier of the license.
+/// * `force` - Replace an existing `LICENSE` file with different contents.
+pub fn set_license(dir: &Path, id: &str, force: bool) -> io::Result<()> {
+    let pyproject_path = dir.join("pyproject.toml");
+    if !pyproject_path.exists() {
+        return Err(io::Error::new(
+            io::ErrorKind::NotFound,
+            format!("pyproject.toml not found in {}.", dir.display()),
+        ));
+    }
+    validate_expression(id)?;
+    let mut doc = pyproject::read(&pyproject_path)?;
+
+    let license = License::find(id);
+    match license {
+        Some(license) => {
+            let text = license.text(&author_name(&doc, dir));
+            let license_path = dir.join(LICENSE_FILE);
+            let unchanged = fs::read_to_string(&license_path).is_ok_and(|old| old == text);
+            if license_path.exists() && !unchanged && !force {
+                return Err(io::Error::new(
+                    io::ErrorKind::AlreadyExists,
+                    "LICENSE already exists; use --force to replace it.",
+                ));
+            }
+            fs::write(&license_path, text)?;
+            println!("Wrote {} ({}).", LICENSE_FILE, license.id);
+        }
+        None => println!(
+            "Warning: no license text is bundled for '{}'; add a LICENSE file yourself. Known licenses: {}.",
+            id,
+            known_ids()
+        ),
+    }
+
+    let with_file = dir.join(LICENSE_FILE).exists();
+    apply(&mut doc, id, with_file)?;
+    pyproject::write(&pyproject_path, &doc)?;
+    println!(
+        "Set the project license to {}.",
+        license.map_or(id, |l| l.id)
+    );
+    Ok(())
+}
+
+/// The first author in `pyproject.toml`, falling back to git's `user.name`.
+fn author_name(doc: &DocumentMut, dir: &Path) -> String {
+    doc.get("project")
+        .and_then(|p| p.get("authors"))
+        .and_then(|a| a.as_array())
+        .and_then(|a| a.get(0))
+        .and_then(|a| a.as_inline_table())
+        .and_then(|a| a.get("name"))
+        .and_then(|n| n.as_str())
+        .map(str::to_string)
+        .or_else(|| git::config_value("user.name", dir))
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn accepts_valid_expressions() {
+        for expression in [
+            "MIT",
+            "GPL-3.0-or-later",
+            "GPL-2.0+",
+            "LicenseRef-Proprietary",
+            "Apache-2.0 OR MIT",
+            "apache-2.0 or mit",
+            "(MIT OR Apache-2.0) AND BSD-3-Clause",
+            "GPL-2.0-or-later WITH Classpath-exception-2.0",
+        ] {
+            assert!(validate_expression(expression).is_ok(), "{}", expression);
+        }
+    }
+
+    #[test]
+    fn rejects_invalid_expressions() {
+        for expression in [
+            "",
+            "MIT License",
+            "MIT OR",
+            "AND MIT",
+            "(MIT",
+            "MIT)",
+            "MIT WITH",
+            "GPL v3",
+            "BSD_3",
+        ] {
+            assert!(validate_expression(expression).is_err(), "{}", expression);
+        }
+    }
+
+    #[test]
+    fn apply_rejects_invalid_expression() {
+        let mut doc = DocumentMut::new();
+        assert!(apply(&mut doc, "MIT License", false).is_err());
+        assert!(doc.get("project").is_none());
+        assert!(apply(&mut doc, "mit", false).is_ok());
+        assert_eq!(doc["project"]["license"].as_str(), Some("MIT"));
+    }
+}
@@ -1,9 +1,32 @@
+mod backend;
 mod build;
+mod changelog;
 mod check;
+mod ci;
+mod cli;
+mod config;
+mod git;
+mod gitignore;
 pub mod helpers;
+mod hooks;
+mod init;
+mod layout;
+mod license;
+mod migrate;
+mod naming;
 mod new;
 mod publish;
+mod pypi;
+mod pyproject;
+mod template;
+mod tools;
+mod version;
+mod wizard;
+mod workspace;
 use clap::{Parser, Subcommand};
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
 
 #[derive(Parser)]
 #[command(name = "hajime")]
@@ -12,42 +35,341 @@ struct Cli {
     #[arg(short, long, action = clap::ArgAction::Count)]
     verbose: u8,
 
+    /// Directory to run in instead of the current one
+    #[arg(
+        short = 'C',
+        long,
+        global = true,
+        value_name = "DIR",
+        help = "Run as if hajime was started in DIR"
+    )]
+    directory: Option<PathBuf>,
+
     #[command(subcommand)]
     command: Commands,
 }
 
 #[derive(Subcommand)]
+#[allow(clippy::large_enum_variant)] // parsed once; boxing would only complicate the match
 enum Commands {
     /// Create a new Python project
     New {
         #[arg(help = "Name of the new project")]
         project_name: String,
 
-        /// Overwrite the existing project if it already exists
+        /// Replace the existing project if it already exists
         #[arg(
             short,
             long,
-            help = "Force project creation, overwriting existing files"
+            help = "Replace an existing project, moving it to a timestamped backup"
         )]
         force: bool,
+
+        /// Show what would be created without writing anything
+        #[arg(long, help = "Print the file tree that would be written and exit")]
+        dry_run: bool,
+
+        /// Do not ask for confirmation
+        #[arg(
+            short,
+            long,
+            help = "Do not ask for confirmation before replacing a project"
+        )]
+        yes: bool,
+
+        /// Template to render the project from
+        #[arg(
+            short,
+            long,
+            help = "Built-in template name or path to a template directory"
+        )]
+        template: Option<String>,
+
+        /// Import name of the package
+        #[arg(
+            long,
+            help = "Import name of the package (derived from the project name by default)"
+        )]
+        import_name: Option<String>,
+
+        /// Package index to check the name against
+        #[arg(
+            long,
+            help = "JSON API URL used to check whether the name is taken (default: https://pypi.org/pypi)"
+        )]
+        index_url: Option<String>,
+
+        /// Package layout (flat or src)
+        #[arg(
+            short,
+            long,
+            help = "Package layout: flat (<project>/<module>, default) or src (<project>/src/<module>)",
+            value_parser = ["flat", "src"]
+        )]
+        layout: Option<String>,
+
+        /// Build backend for the generated pyproject.toml
+        #[arg(
+            long,
+            help = "Build backend for pyproject.toml",
+            value_parser = ["hatchling", "setuptools", "flit", "pdm", "maturin", "uv"]
+        )]
+        backend: Option<String>,
+
+        /// Scaffold a mixed Rust/Python project (PyO3 + maturin)
+        #[arg(
+            long,
+            help = "Scaffold a mixed Rust/Python project using PyO3 and maturin"
+        )]
+        rust: bool,
+
+        /// Ask for the project settings interactively
+        #[arg(
+            short,
+            long,
+            help = "Ask for description, author, license, backend, ..."
+        )]
+        interactive: bool,
+
+        /// Project description
+        #[arg(long, help = "Project description for pyproject.toml and README.md")]
+        description: Option<String>,
+
+        /// Author name
+        #[arg(long, help = "Author name (defaults to git config user.name)")]
+        author: Option<String>,
+
+        /// Author email
+        #[arg(long, help = "Author email (defaults to git config user.email)")]
+        author_email: Option<String>,
+
+        /// License
+        #[arg(
+            long,
+            help = "SPDX license identifier, e.g. MIT or Apache-2.0; writes LICENSE"
+        )]
+        license: Option<String>,
+
+        /// Minimum Python version
+        #[arg(
+            long = "python",
+            help = "Minimum Python version for requires-python, e.g. 3.10"
+        )]
+        python_version: Option<String>,
+
+        /// Register a console script
+        #[arg(long, help = "Register the example function as a console script")]
+        entry_point: bool,
+
+        /// Generate a command-line interface
+        #[arg(
+            long,
+            num_args = 0..=1,
+            default_missing_value = "argparse",
+            help = "Generate cli.py and __main__.py and register a console script (default: argparse)",
+            value_parser = ["argparse", "click", "typer"]
+        )]
+        cli: Option<String>,
+
+        /// Configure mypy
+        #[arg(long, help = "Configure mypy and add a py.typed marker")]
+        mypy: bool,
+
+        /// Use the strict tool profile
+        #[arg(
+            long,
+            help = "Stricter ruff rules, pytest settings and mypy --strict (implies --mypy)"
+        )]
+        strict: bool,
+
+        /// Generate a CI workflow
+        #[arg(
+            long,
+            help = "Generate a CI workflow that lints, tests and publishes on tags",
+            value_parser = ["github", "gitlab"]
+        )]
+        ci: Option<String>,
+
+        /// Set up pre-commit hooks
+        #[arg(
+            long,
+            help = "Write .pre-commit-config.yaml and install a Git pre-commit hook"
+        )]
+        pre_commit: bool,
+
+        /// Commit the generated skeleton
+        #[arg(long, help = "Commit the generated files to the new Git repository")]
+        initial_commit: bool,
+
+        /// Default branch name
+        #[arg(long, help = "Name of the default branch, e.g. main")]
+        branch: Option<String>,
+
+        /// Remote repository URL
+        #[arg(long, help = "URL of the Git remote to add as origin")]
+        remote: Option<String>,
+
+        /// .gitignore presets
+        #[arg(
+            long,
+            value_delimiter = ',',
+            help = "Comma-separated .gitignore presets added to python (default: jupyter,macos,editors)",
+            value_parser = ["python", "rust", "jupyter", "macos", "editors", "data"]
+        )]
+        gitignore: Vec<String>,
+
+        /// Create a uv workspace root
+        #[arg(
+            long,
+            help = "Create a uv workspace root; run `hajime new` inside it to add member packages"
+        )]
+        workspace: bool,
     },
+    /// Turn the current directory into a Python project without overwriting files
+    Init {
+        /// Project name (defaults to the directory name)
+        #[arg(short, long, help = "Project name (defaults to the directory name)")]
+        name: Option<String>,
+
+        /// Template to take the missing files from
+        #[arg(
+            short,
+            long,
+            help = "Built-in template name or path to a template directory"
+        )]
+        template: Option<String>,
+
+        /// Import name of the package
+        #[arg(
+            long,
+            help = "Import name of the package (derived from the project name by default)"
+        )]
+        import_name: Option<String>,
+
+        /// Package layout if no package directory exists yet
+        #[arg(
+            short,
+            long,
+            help = "Package layout used when no package directory exists yet (default: flat)",
+            value_parser = ["flat", "src"]
+        )]
+        layout: Option<String>,
+
+        /// Build backend for a newly written pyproject.toml
+        #[arg(
+            long,
+            help = "Build backend for pyproject.toml",
+            value_parser = ["hatchling", "setuptools", "flit", "pdm", "uv"]
+        )]
+        backend: Option<String>,
+    },
+    /// Manage CI workflows
+    Ci {
+        #[command(subcommand)]
+        command: CiCommands,
+    },
+    /// Manage the .gitignore file
+    Gitignore {
+        #[command(subcommand)]
+        command: GitignoreCommands,
+    },
+    /// Manage Git hooks
+    Hooks {
+        #[command(subcommand)]
+        command: HooksCommands,
+    },
+    /// Check project names
+    Name {
+        #[command(subcommand)]
+        command: NameCommands,
+    },
+    /// Manage the project license
+    License {
+        #[command(subcommand)]
+        command: LicenseCommands,
+    },
+    /// Print, bump or set the project version
+    Version {
+        #[command(subcommand)]
+        command: Option<VersionCommands>,
+
+        /// Show the change without writing it
+        #[arg(long, global = true, help = "Print the new version without writing it")]
+        dry_run: bool,
+
+        /// Print JSON output
+        #[arg(long, global = true, help = "Print the result as JSON")]
+        json: bool,
+    },
+    /// Write the changes since the last release to CHANGELOG.md
+    Changelog {
+        /// Version to title the new section with
+        #[arg(
+            long,
+            help = "Title the new section with this version instead of Unreleased"
+        )]
+        release: Option<String>,
+
+        /// Print the new section without writing it
+        #[arg(long, help = "Print the new section without writing it")]
+        dry_run: bool,
+    },
+    /// Manage default settings
+    Config {
+        #[command(subcommand)]
+        command: ConfigCommands,
+    },
+    /// Migrate setup.py, setup.cfg and requirements*.txt into pyproject.toml
+    Migrate,
     /// Build the Python project
     Build {
         /// Use maturin to build the project
         #[arg(short = 'm', long, help = "Use maturin to build the project")]
         maturin: bool,
 
-        /// Bump the project version (macro, meso, micro)
+        /// Bump the project version
         #[arg(
             short = 'b',
             long,
-            help = "Bump the project version (macro, meso, micro)",
-            value_parser = ["macro", "meso", "micro"]
+            help = "Bump the project version (major, minor, patch, pre, rc, beta, alpha, post, dev; macro, meso and micro are aliases)",
+            value_parser = ["major", "minor", "patch", "pre", "rc", "beta", "alpha", "post", "dev", "macro", "meso", "micro"]
         )]
         bump_version: Option<String>,
+
+        /// Set the project version
+        #[arg(
+            long,
+            conflicts_with = "bump_version",
+            help = "Set the project version to a PEP 440 version"
+        )]
+        set_version: Option<String>,
+
+        /// Workspace member to operate on
+        #[arg(long, help = "Workspace member to operate on")]
+        package: Option<String>,
+
+        /// Operate on all workspace members
+        #[arg(
+            long,
+            conflicts_with = "package",
+            help = "Operate on all workspace members in dependency order"
+        )]
+        all: bool,
     },
     /// Check th build of the Python project
-    Check,
+    Check {
+        /// Workspace member to operate on
+        #[arg(long, help = "Workspace member to operate on")]
+        package: Option<String>,
+
+        /// Operate on all workspace members
+        #[arg(
+            long,
+            conflicts_with = "package",
+            help = "Operate on all workspace members in dependency order"
+        )]
+        all: bool,
+    },
     /// Publish the project to PyPI
     Publish {
         /// PyPI account to use (default if not specified)
@@ -65,40 +387,455 @@ enum Commands {
         /// Use maturin for uploading Rust-based Python projects
         #[arg(long, help = "Use maturin for uploading Rust-based Python projects")]
         maturin: bool,
+
+        /// Workspace member to operate on
+        #[arg(long, help = "Workspace member to operate on")]
+        package: Option<String>,
+
+        /// Operate on all workspace members
+        #[arg(
+            long,
+            conflicts_with = "package",
+            help = "Operate on all workspace members in dependency order"
+        )]
+        all: bool,
+    },
+}
+
+#[derive(Subcommand)]
+enum CiCommands {
+    /// Write the CI workflow for the project in the current directory
+    Generate {
+        #[arg(
+            default_value = "github",
+            help = "CI service to generate the workflow for",
+            value_parser = ["github", "gitlab"]
+        )]
+        provider: String,
+
+        /// Replace an existing workflow file
+        #[arg(short, long, help = "Replace an existing workflow file")]
+        force: bool,
+    },
+}
+
+#[derive(Subcommand)]
+enum GitignoreCommands {
+    /// Merge presets into .gitignore without duplicating patterns
+    Add {
+        #[arg(
+            required = true,
+            help = "Presets to add",
+            value_parser = ["python", "rust", "jupyter", "macos", "editors", "data"]
+        )]
+        presets: Vec<String>,
+    },
+}
+
+#[derive(Subcommand)]
+enum HooksCommands {
+    /// Install a Git pre-commit hook running [tool.hajime.hooks] pre-commit (default: ruff)
+    Install {
+        /// Replace an existing hook
+        #[arg(
+            short,
+            long,
+            help = "Replace an existing pre-commit hook not installed by hajime"
+        )]
+        force: bool,
+    },
+}
+
+#[derive(Subcommand)]
+enum NameCommands {
+    /// Check whether a name is valid, available and not confusable with popular packages
+    Check {
+        #[arg(help = "Project name to check")]
+        name: String,
+
+        /// Package index to check the name against
+        #[arg(
+            long,
+            help = "JSON API URL used to check whether the name is taken (default: https://pypi.org/pypi)"
+        )]
+        index_url: Option<String>,
+    },
+}
+
+#[derive(Subcommand)]
+enum LicenseCommands {
+    /// Write LICENSE and set the license in pyproject.toml
+    Set {
+        #[arg(help = "SPDX license identifier, e.g. MIT, Apache-2.0 or GPL-3.0-or-later")]
+        id: String,
+
+        /// Replace an existing LICENSE file
+        #[arg(short, long, help = "Replace an existing LICENSE file")]
+        force: bool,
+    },
+}
+
+#[derive(Subcommand)]
+enum VersionCommands {
+    /// Bump the project version
+    Bump {
+        #[arg(
+            required_unless_present = "auto",
+            help = "Part to bump: major, minor, patch, pre, rc, beta, alpha, post or dev",
+            value_parser = ["major", "minor", "patch", "pre", "rc", "beta", "alpha", "post", "dev", "macro", "meso", "micro"]
+        )]
+        level: Option<String>,
+
+        /// Choose the level from the commits since the last release
+        #[arg(
+            long,
+            conflicts_with = "level",
+            help = "Choose major, minor or patch from the Conventional Commits since the last release"
+        )]
+        auto: bool,
+    },
+    /// Set the project version
+    Set {
+        #[arg(help = "New PEP 440 version, e.g. 1.2.0 or 2.0.0rc1")]
+        version: String,
+    },
+    /// Check that all version files declare the same version
+    Check,
+}
+
+#[derive(Subcommand)]
+enum ConfigCommands {
+    /// Print the effective value of a setting
+    Get {
+        #[arg(help = "Setting name, e.g. author or license")]
+        key: String,
+    },
+    /// Store a setting in the user configuration file
+    Set {
+        #[arg(help = "Setting name, e.g. author or license")]
+        key: String,
+
+        #[arg(help = "Value of the setting")]
+        value: String,
+
+        /// Store the setting in [tool.hajime] of the current project
+        #[arg(
+            long,
+            help = "Store the setting in [tool.hajime] of pyproject.toml instead"
+        )]
+        project: bool,
     },
+    /// List all configured settings and where they come from
+    List,
 }
 
-fn main() {
+fn main() -> ExitCode {
     let cli = Cli::parse();
+    let directory = cli.directory.as_deref().unwrap_or(Path::new("."));
+    if !directory.is_dir() {
+        eprintln!("Error: {} is not a directory.", directory.display());
+        return ExitCode::FAILURE;
+    }
+    // Project commands also work from subdirectories such as `tests/`
+    let root = helpers::find_project_root(directory);
+    // Loaded only by the commands that use it, so `hajime config set` can repair a broken file
+    let load_config = || {
+        config::Config::load(&root)
+            .map_err(|e| io::Error::new(e.kind(), format!("Invalid configuration: {}", e)))
+    };
 
     match &cli.command {
         Commands::New {
             project_name,
             force,
+            dry_run,
+            yes,
+            template,
+            import_name,
+            index_url,
+            layout,
+            backend,
+            rust,
+            interactive,
+            description,
+            author,
+            author_email,
+            license,
+            python_version,
+            entry_point,
+            cli,
+            mypy,
+            strict,
+            ci,
+            pre_commit,
+            initial_commit,
+            branch,
+            remote,
+            gitignore,
+            workspace,
         } => {
-            if let Err(e) = new::create_project(project_name, *force) {
+            let result = load_config().and_then(|config| {
+                let layout = config.resolve(layout.as_deref(), "layout")?;
+                let mut options = new::NewOptions {
+                    force: *force,
+                    dry_run: *dry_run,
+                    yes: *yes,
+                    template: template.clone().or_else(|| config.get("template")),
+                    import_name: import_name.clone(),
+                    index_url: Some(pypi::index_url(
+                        index_url.as_deref(),
+                        config.get("index-url").as_deref(),
+                    )),
+                    layout: layout.unwrap_or_default(),
+                    backend: config.resolve(backend.as_deref(), "backend")?,
+                    rust: *rust,
+                    description: description.clone(),
+                    author: author.clone().or_else(|| config.get("author")),
+                    author_email: author_email.clone().or_else(|| config.get("author-email")),
+                    license: license.clone().or_else(|| config.get("license")),
+                    python_version: python_version
+                        .clone()
+                        .or_else(|| config.get("python-version")),
+                    entry_point: *entry_point,
+                    cli: cli
+                        .as_deref()
+                        .map(|c| c.parse().expect("validated by clap")),
+                    mypy: *mypy,
+                    strict: *strict,
+                    ci: ci.as_deref().map(|c| c.parse().expect("validated by clap")),
+                    pre_commit: *pre_commit,
+                    initial_commit: *initial_commit,
+                    branch: branch.clone(),
+                    remote: remote.clone(),
+                    gitignore: config.resolve_list(gitignore, "gitignore")?,
+                    workspace: *workspace,
+                };
+                if *interactive {
+                    wizard::run(directory, project_name, &mut options)?;
+                }
+                new::create_project(directory, project_name, &options)
+            });
+            if let Err(e) = result {
                 eprintln!("Error creating project: {}", e);
+                return ExitCode::FAILURE;
+            }
+        }
+        Commands::Init {
+            name,
+            template,
+            import_name,
+            layout,
+            backend,
+        } => {
+            let result = load_config().and_then(|config| {
+                let layout = config.resolve(layout.as_deref(), "layout")?;
+                let options = new::NewOptions {
+                    template: template.clone().or_else(|| config.get("template")),
+                    import_name: import_name.clone(),
+                    layout: layout.unwrap_or_default(),
+                    backend: config.resolve(backend.as_deref(), "backend")?,
+                    author: config.get("author"),
+                    author_email: config.get("author-email"),
+                    license: config.get("license"),
+                    python_version: config.get("python-version"),
+                    gitignore: config.resolve_list(&[], "gitignore")?,
+                    ..Default::default()
+                };
+                init::init_project(directory, name.as_deref(), &options)
+            });
+            if let Err(e) = result {
+                eprintln!("Error initializing project: {}", e);
+                return ExitCode::FAILURE;
+            }
+        }
+        Commands::Ci {
+            command: CiCommands::Generate { provider, force },
+        } => {
+            let provider = provider.parse().expect("validated by clap");
+            if let Err(e) = ci::generate(&root, provider, *force) {
+                eprintln!("Error generating CI workflow: {}", e);
+                return ExitCode::FAILURE;
+            }
+        }
+        Commands::Gitignore {
+            command: GitignoreCommands::Add { presets },
+        } => {
+            let presets: Vec<gitignore::Preset> = presets
+                .iter()
+                .map(|p| p.parse().expect("validated by clap"))
+                .collect();
+            if let Err(e) = gitignore::add(&root, &presets) {
+                eprintln!("Error updating .gitignore: {}", e);
+                return ExitCode::FAILURE;
+            }
+        }
+        Commands::Hooks {
+            command: HooksCommands::Install { force },
+        } => {
+            if let Err(e) = hooks::install(&root, *force) {
+                eprintln!("Error installing hooks: {}", e);
+                return ExitCode::FAILURE;
+            }
+        }
+        Commands::Name {
+            command: NameCommands::Check { name, index_url },
+        } => {
+            let result = load_config().and_then(|config| {
+                let index_url =
+                    pypi::index_url(index_url.as_deref(), config.get("index-url").as_deref());
+                naming::check_name(name, Some(&index_url))
+            });
+            if let Err(e) = result {
+                eprintln!("Error checking name: {}", e);
+                return ExitCode::FAILURE;
+            }
+        }
+        Commands::License {
+            command: LicenseCommands::Set { id, force },
+        } => {
+            if let Err(e) = license::set_license(&root, id, *force) {
+                eprintln!("Error setting license: {}", e);
+                return ExitCode::FAILURE;
+            }
+        }
+        Commands::Version {
+            command,
+            dry_run,
+            json,
+        } => {
+            let action = match command {
+                None => Ok(version::Action::Show),
+                Some(VersionCommands::Check) => Ok(version::Action::Check),
+                Some(VersionCommands::Bump { level, auto }) => Ok(match level {
+                    Some(level) if !auto => {
+                        version::Action::Bump(level.parse().expect("validated by clap"))
+                    }
+                    _ => version::Action::AutoBump,
+                }),
+                Some(VersionCommands::Set { version }) => version
+                    .parse()
+                    .map(version::Action::Set)
+                    .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e)),
+            };
+            if let Err(e) = action.and_then(|action| version::run(&root, action, *dry_run, *json)) {
+                eprintln!("Error managing version: {}", e);
+                return ExitCode::FAILURE;
+            }
+        }
+        Commands::Changelog { release, dry_run } => {
+            let result = release
+                .as_deref()
+                .map(str::parse)
+                .transpose()
+                .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))
+                .and_then(|release| changelog::generate(&root, release.as_ref(), *dry_run));
+            if let Err(e) = result {
+                eprintln!("Error writing changelog: {}", e);
+                return ExitCode::FAILURE;
+            }
+        }
+        Commands::Config { command } => {
+            let result = match command {
+                ConfigCommands::Get { key } => config::get(&root, key),
+                ConfigCommands::Set {
+                    key,
+                    value,
+                    project,
+                } => config::set(&root, key, value, *project),
+                ConfigCommands::List => config::list(&root),
+            };
+            if let Err(e) = result {
+                eprintln!("Error managing configuration: {}", e);
+                return ExitCode::FAILURE;
+            }
+        }
+        Commands::Migrate => {
+            if let Err(e) = migrate::migrate_project(directory) {
+                eprintln!("Error migrating project: {}", e);
+                return ExitCode::FAILURE;
             }
         }
         Commands::Build {
             maturin,
             bump_version,
+            set_version,
+            package,
+            all,
         } => {
-            build::build_project(*maturin, bump_version.as_deref());
+            let bump_version = bump_version
+                .as_deref()
+                .map(|b| b.parse().expect("validated by clap"));
+            let set_version = match set_version.as_deref().map(str::parse).transpose() {
+                Ok(set_version) => set_version,
+                Err(e) => {
+                    eprintln!("Error building project: {}", e);
+                    return ExitCode::FAILURE;
+                }
+            };
+            let result = load_config().and_then(|config| {
+                let index_url = pypi::index_url(None, config.get("index-url").as_deref());
+                let dirs = workspace::project_dirs(&root, package.as_deref(), *all)?;
+                // Members are in dependency order, so stop before building dependents
+                dirs.iter().try_for_each(|dir| {
+                    print_member_header(dir, dirs.len());
+                    build::build_project(
+                        dir,
+                        *maturin,
+                        bump_version,
+                        set_version.as_ref(),
+                        &index_url,
+                    )
+                })
+            });
+            if let Err(e) = result {
+                eprintln!("Error building project: {}", e);
+                return ExitCode::FAILURE;
+            }
         }
-        Commands::Check => {
-            if let Err(e) = check::check_package() {
+        Commands::Check { package, all } => {
+            let result =
+                workspace::project_dirs(&root, package.as_deref(), *all).and_then(|dirs| {
+                    dirs.iter().try_for_each(|dir| {
+                        print_member_header(dir, dirs.len());
+                        check::check_package(dir)
+                    })
+                });
+            if let Err(e) = result {
                 eprintln!("Error checking package: {}", e);
+                return ExitCode::FAILURE;
             }
         }
         Commands::Publish {
             account,
             override_token,
             maturin,
+            package,
+            all,
         } => {
-            if let Err(e) = publish::publish_package(account.clone(), *override_token, *maturin) {
+            let result = load_config().and_then(|config| {
+                let dirs = workspace::project_dirs(&root, package.as_deref(), *all)?;
+                dirs.iter().try_for_each(|dir| {
+                    print_member_header(dir, dirs.len());
+                    publish::publish_package(
+                        dir,
+                        account.clone().or_else(|| config.get("account")),
+                        *override_token,
+                        *maturin,
+                    )
+                })
+            });
+            if let Err(e) = result {
                 eprintln!("Error publishing package: {}", e);
+                return ExitCode::FAILURE;
             }
         }
     }
+    ExitCode::SUCCESS
+}
+
+/// Announces which workspace member a command is running in when there are several.
+fn print_member_header(dir: &Path, count: usize) {
+    if count > 1 {
+        println!("\n==> {}", dir.display());
+    }
 }
@@ -0,0 +1,1077 @@
+use crate::backend::Backend;
+use crate::helpers::read_requirements;
+use crate::license::{is_license_classifier, License};
+use crate::pyproject::{self, string_array};
+use std::fs;
+use std::io;
+use std::path::Path;
+use toml_edit::{value, Array, DocumentMut, InlineTable};
+
+/// setup() arguments that modern build backends handle on their own.
+const BACKEND_KEYS: &[&str] = &[
+    "packages",
+    "package_dir",
+    "py_modules",
+    "package_data",
+    "include_package_data",
+    "exclude_package_data",
+    "zip_safe",
+    "setup_requires",
+    "platforms",
+    "long_description_content_type",
+];
+
+/// A static value read from `setup.py` or `setup.cfg`.
+enum Literal {
+    Str(String),
+    /// `True` or `False`; only meaningful for settings we do not translate.
+    Bool,
+    List(Vec<Literal>),
+    Dict(Vec<(String, Literal)>),
+}
+
+impl Literal {
+    /// Interprets the value as a list of strings.
+    /// Strings are split into lines, as setup.cfg and `install_requires` allow.
+    fn strings(&self) -> Option<Vec<String>> {
+        match self {
+            Literal::Str(s) => Some(
+                s.lines()
+                    .map(str::trim)
+                    .filter(|l| !l.is_empty() && !l.starts_with('#'))
+                    .map(str::to_string)
+                    .collect(),
+            ),
+            Literal::List(items) => items
+                .iter()
+                .map(|item| match item {
+                    Literal::Str(s) => Some(s.trim().to_string()),
+                    _ => None,
+                })
+                .collect(),
+            _ => None,
+        }
+    }
+
+    fn as_str(&self) -> Option<&str> {
+        match self {
+            Literal::Str(s) => Some(s.trim()),
+            _ => None,
+        }
+    }
+}
+
+/// Project metadata collected from the legacy files, in PEP 621 terms.
+#[derive(Default)]
+struct Metadata {
+    name: Option<String>,
+    version: Option<String>,
+    description: Option<String>,
+    readme: Option<String>,
+    author: (Option<String>, Option<String>),
+    maintainer: (Option<String>, Option<String>),
+    license: Option<String>,
+    license_files: Vec<String>,
+    requires_python: Option<String>,
+    keywords: Vec<String>,
+    classifiers: Vec<String>,
+    urls: Vec<(String, String)>,
+    dependencies: Vec<String>,
+    optional_dependencies: Vec<(String, Vec<String>)>,
+    entry_points: Vec<(String, Vec<(String, String)>)>,
+    /// Settings that could not be translated, with the reason.
+    skipped: Vec<String>,
+}
+
+impl Metadata {
+    /// Records one `setup()` argument or setup.cfg option.
+    fn apply(&mut self, source: &str, key: &str, literal: Option<Literal>) {
+        let key = key.replace('-', "_");
+        let Some(literal) = literal else {
+            self.skip(source, &key, "value is not a static literal");
+            return;
+        };
+
+        match key.as_str() {
+            "name" | "version" | "description" | "python_requires" => match literal.as_str() {
+                Some(v) if v.starts_with("attr:") || v.starts_with("file:") => {
+                    self.skip(source, &key, "dynamic values are not supported")
+                }
+                Some(v) => {
+                    let v = Some(v.to_string());
+                    match key.as_str() {
+                        "name" => self.name = v,
+                        "version" => self.version = v,
+                        "description" => self.description = v,
+                        _ => self.requires_python = v,
+                    }
+                }
+                None => self.skip(source, &key, "expected a string"),
+            },
+            "long_description" => match literal.as_str().and_then(|v| v.strip_prefix("file:")) {
+                Some(files) => {
+                    self.readme = files.split(',').next().map(|f| f.trim().to_string());
+                }
+                None => self.skip(
+                    source,
+                    &key,
+                    "inline text; point `readme` at a file instead",
+                ),
+            },
+            "author" | "author_email" | "maintainer" | "maintainer_email" => {
+                let Some(v) = literal.as_str() else {
+                    return self.skip(source, &key, "expected a string");
+                };
+                let person = if key.starts_with("author") {
+                    &mut self.author
+                } else {
+                    &mut self.maintainer
+                };
+                if key.ends_with("_email") {
+                    person.1 = Some(v.to_string());
+                } else {
+                    person.0 = Some(v.to_string());
+                }
+            }
+            "license" => match literal.as_str() {
+                Some(v) => self.license = Some(v.to_string()),
+                None => self.skip(source, &key, "expected a string"),
+            },
+            "license_file" | "license_files" => match literal.strings() {
+                Some(files) => self.license_files.extend(split_commas(&files)),
+                None => self.skip(source, &key, "expected a list of files"),
+            },
+            "keywords" => match literal.strings() {
+                Some(words) => self.keywords.extend(
+                    split_commas(&words)
+                        .iter()
+                        .flat_map(|w| w.split_whitespace())
+                        .map(str::to_string),
+                ),
+                None => self.skip(source, &key, "expected a list of strings"),
+            },
+            "classifiers" | "install_requires" => match literal.strings() {
+                Some(items) if key == "classifiers" => self.classifiers.extend(items),
+                Some(items) => self.dependencies.extend(items),
+                None => self.skip(source, &key, "expected a list of strings"),
+            },
+            "url" | "download_url" => match literal.as_str() {
+                Some(v) => {
+                    let label = if key == "url" { "Homepage" } else { "Download" };
+                    self.urls.push((label.to_string(), v.to_string()));
+                }
+                None => self.skip(source, &key, "expected a string"),
+            },
+            "project_urls" => match literal {
+                Literal::Dict(entries) => {
+                    for (label, url) in entries {
+                        match url.as_str() {
+                            Some(url) => self.urls.push((label, url.to_string())),
+                            None => self.skip(source, &key, "expected string URLs"),
+                        }
+                    }
+                }
+                Literal::Str(s) => self.urls.extend(key_value_lines(&s)),
+                _ => self.skip(source, &key, "expected a mapping"),
+            },
+            "extras_require" => match literal {
+                Literal::Dict(entries) => {
+                    for (extra, requirements) in entries {
+                        match requirements.strings() {
+                            Some(reqs) => self.optional_dependencies.push((extra, reqs)),
+                            None => self.skip(source, &key, "expected lists of requirements"),
+                        }
+                    }
+                }
+                _ => self.skip(source, &key, "expected a mapping"),
+            },
+            "entry_points" => match literal {
+                Literal::Dict(groups) => {
+                    for (group, entries) in groups {
+                        let entries = match entries {
+                            Literal::Str(s) => Some(key_value_lines(&s)),
+                            other => other
+                                .strings()
+                                .map(|lines| key_value_lines(&lines.join("\n"))),
+                        };
+                        match entries {
+                            Some(entries) => self.entry_points.push((group, entries)),
+                            None => self.skip(source, &key, "expected lists of entry points"),
+                        }
+                    }
+                }
+                _ => self.skip(source, &key, "expected a mapping"),
+            },
+            _ if BACKEND_KEYS.contains(&key.as_str()) => {
+                self.skip(source, &key, "handled by the build backend")
+            }
+            _ => self.skip(source, &key, "no pyproject.toml equivalent"),
+        }
+    }
+
+    /// Combines metadata from two sources; fields set in `over` replace those in `self`.
+    /// Lists are replaced as a whole, keyed entries (URLs, extras, entry point groups)
+    /// one by one.
+    fn merge(mut self, over: Metadata) -> Metadata {
+        fn replace<T>(base: &mut Vec<T>, over: Vec<T>) {
+            if !over.is_empty() {
+                *base = over;
+            }
+        }
+        fn replace_keyed<V>(base: &mut Vec<(String, V)>, over: Vec<(String, V)>) {
+            for (key, value) in over {
+                match base.iter_mut().find(|(k, _)| *k == key) {
+                    Some(entry) => entry.1 = value,
+                    None => base.push((key, value)),
+                }
+            }
+        }
+
+        self.name = over.name.or(self.name);
+        self.version = over.version.or(self.version);
+        self.description = over.description.or(self.description);
+        self.readme = over.readme.or(self.readme);
+        self.author.0 = over.author.0.or(self.author.0);
+        self.author.1 = over.author.1.or(self.author.1);
+        self.maintainer.0 = over.maintainer.0.or(self.maintainer.0);
+        self.maintainer.1 = over.maintainer.1.or(self.maintainer.1);
+        self.license = over.license.or(self.license);
+        self.requires_python = over.requires_python.or(self.requires_python);
+        replace(&mut self.license_files, over.license_files);
+        replace(&mut self.keywords, over.keywords);
+        replace(&mut self.classifiers, over.classifiers);
+        replace(&mut self.dependencies, over.dependencies);
+        replace_keyed(&mut self.urls, over.urls);
+        replace_keyed(&mut self.optional_dependencies, over.optional_dependencies);
+        replace_keyed(&mut self.entry_points, over.entry_points);
+        self.skipped.extend(over.skipped);
+        self
+    }
+
+    fn skip(&mut self, source: &str, key: &str, reason: &str) {
+        self.skipped
+            .push(format!("{}: {} ({})", source, key, reason));
+    }
+
+    /// Writes the collected metadata into the `[project]` table.
+    fn write(&self, doc: &mut DocumentMut) -> Vec<&'static str> {
+        let mut written = Vec::new();
+        let project = pyproject::table_mut(doc, &["project"]);
+
+        let strings = [
+            ("name", &self.name),
+            ("version", &self.version),
+            ("description", &self.description),
+            ("readme", &self.readme),
+            ("requires-python", &self.requires_python),
+        ];
+        for (key, field) in strings {
+            if let Some(v) = field {
+                project[key] = value(v.as_str());
+                written.push(key);
+            }
+        }
+
+        if let Some(license) = &self.license {
+            // PEP 639 expects an SPDX expression; keep anything else, such as `BSD`,
+            // in the legacy table form
+            if let Some(spdx) = License::find(license) {
+                project["license"] = value(spdx.id);
+            } else {
+                let mut text = InlineTable::new();
+                text.insert("text", license.as_str().into());
+                project["license"] = value(text);
+            }
+            written.push("license");
+        }
+
+        let people = [("authors", &self.author), ("maintainers", &self.maintainer)];
+        for (key, (name, email)) in people {
+            if name.is_none() && email.is_none() {
+                continue;
+            }
+            let mut person = InlineTable::new();
+            if let Some(name) = name {
+                person.insert("name", name.as_str().into());
+            }
+            if let Some(email) = email {
+                person.insert("email", email.as_str().into());
+            }
+            let mut list = Array::new();
+            list.push(person);
+            project[key] = value(list);
+            written.push(key);
+        }
+
+        let lists = [
+            ("license-files", &self.license_files),
+            ("keywords", &self.keywords),
+            ("classifiers", &self.classifiers),
+            ("dependencies", &self.dependencies),
+        ];
+        for (key, items) in lists {
+            if !items.is_empty() {
+                project[key] = value(string_array(items));
+                written.push(key);
+            }
+        }
+
+        if !self.urls.is_empty() {
+            let urls = pyproject::table_mut(doc, &["project", "urls"]);
+            for (label, url) in &self.urls {
+                urls[label.as_str()] = value(url.as_str());
+            }
+            written.push("urls");
+        }
+
+        if !self.optional_dependencies.is_empty() {
+            let extras = pyproject::table_mut(doc, &["project", "optional-dependencies"]);
+            for (extra, requirements) in &self.optional_dependencies {
+                extras[extra.as_str()] = value(string_array(requirements));
+            }
+            written.push("optional-dependencies");
+        }
+
+        for (group, entries) in &self.entry_points {
+            let (path, key): (Vec<&str>, _) = match group.as_str() {
+                "console_scripts" => (vec!["project", "scripts"], "scripts"),
+                "gui_scripts" => (vec!["project", "gui-scripts"], "gui-scripts"),
+                _ => (
+                    vec!["project", "entry-points", group.as_str()],
+                    "entry-points",
+                ),
+            };
+            let table = pyproject::table_mut(doc, &path);
+            for (name, target) in entries {
+                table[name.as_str()] = value(target.as_str());
+            }
+            if !written.contains(&key) {
+                written.push(key);
+            }
+        }
+
+        written
+    }
+}
+
+/// Translates `setup.cfg`, static `setup.py` arguments and `requirements*.txt`
+/// in `base_path` into a PEP 621 `[project]` table in `pyproject.toml`.
+pub fn migrate_project(base_path: &Path) -> io::Result<()> {
+    let mut metadata = Metadata::default();
+    let mut sources = Vec::new();
+
+    let setup_cfg = base_path.join("setup.cfg");
+    if setup_cfg.exists() {
+        read_setup_cfg(&fs::read_to_string(&setup_cfg)?, &mut metadata);
+        sources.push("setup.cfg");
+    }
+
+    // setup() arguments take precedence over setup.cfg, as in setuptools
+    let setup_py = base_path.join("setup.py");
+    if setup_py.exists() {
+        let mut from_setup_py = Metadata::default();
+        read_setup_py(&fs::read_to_string(&setup_py)?, &mut from_setup_py)?;
+        metadata = metadata.merge(from_setup_py);
+        sources.push("setup.py");
+    }
+
+    let requirements = read_requirements_files(base_path, &mut metadata)?;
+    sources.extend(requirements.iter().map(String::as_str));
+
+    if sources.is_empty() {
+        return Err(io::Error::new(
+            io::ErrorKind::NotFound,
+            format!(
+                "No setup.py, setup.cfg or requirements*.txt found in {}.",
+                base_path.display()
+            ),
+        ));
+    }
+    println!("Reading {}...", sources.join(", "));
+
+    if metadata.readme.is_none() {
+        metadata.readme = ["README.md", "README.rst", "README.txt", "README"]
+            .into_iter()
+            .find(|f| base_path.join(f).exists())
+            .map(str::to_string);
+    }
+
+    // License classifiers are dropped next to a license expression, as in `hajime license set`
+    if metadata
+        .license
+        .as_deref()
+        .is_some_and(|l| License::find(l).is_some())
+    {
+        let before = metadata.classifiers.len();
+        metadata.classifiers.retain(|c| !is_license_classifier(c));
+        if metadata.classifiers.len() < before {
+            metadata.skip(
+                "classifiers",
+                "License :: ...",
+                "replaced by the `license` expression",
+            );
+        }
+    }
+
+    let pyproject_path = base_path.join("pyproject.toml");
+    let mut doc = if pyproject_path.exists() {
+        pyproject::read(&pyproject_path)?
+    } else {
+        DocumentMut::new()
+    };
+    if pyproject::build_backend(&doc).is_none() {
+        // Legacy projects were built with setuptools, so keep using it
+        Backend::Setuptools.set_build_system(&mut doc);
+    }
+
+    let written = metadata.write(&mut doc);
+    pyproject::write(&pyproject_path, &doc)?;
+    println!(
+        "Wrote [project] fields to pyproject.toml: {}",
+        written.join(", ")
+    );
+
+    if !metadata.skipped.is_empty() {
+        println!("\nCould not translate:");
+        for item in &metadata.skipped {
+            println!("  - {}", item);
+        }
+    }
+
+    println!(
+        "\nThe legacy files were left in place; remove them once `hajime build` works with pyproject.toml."
+    );
+    Ok(())
+}
+
+/// Reads dependencies from `requirements.txt` and extras from `requirements-<extra>.txt`.
+/// Returns the names of the files that were read.
+fn read_requirements_files(dir: &Path, metadata: &mut Metadata) -> io::Result<Vec<String>> {
+    let mut files: Vec<String> = fs::read_dir(dir)?
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.file_name().to_string_lossy().into_owned())
+        .filter(|name| name.starts_with("requirements") && name.ends_with(".txt"))
+        .collect();
+    files.sort();
+
+    for file in &files {
+        let requirements = read_requirements(&dir.join(file))?;
+        let stem = file.trim_end_matches(".txt");
+        match stem.strip_prefix("requirements").unwrap_or("") {
+            "" => {
+                if metadata.dependencies.is_empty() {
+                    metadata.dependencies = requirements;
+                }
+            }
+            suffix => {
+                let extra = suffix.trim_start_matches(['-', '_']).to_string();
+                if !metadata
+                    .optional_dependencies
+                    .iter()
+                    .any(|(e, _)| *e == extra)
+                {
+                    metadata.optional_dependencies.push((extra, requirements));
+                }
+            }
+        }
+    }
+
+    Ok(files)
+}
+
+/// Reads the `[metadata]` and `[options*]` sections of a setup.cfg file.
+fn read_setup_cfg(content: &str, metadata: &mut Metadata) {
+    for (section, entries) in parse_ini(content) {
+        match section.as_str() {
+            "metadata" | "options" => {
+                for (key, v) in entries {
+                    metadata.apply("setup.cfg", &key, Some(cfg_literal(&key, v)));
+                }
+            }
+            "options.extras_require" | "options.entry_points" => {
+                let key = section.trim_start_matches("options.");
+                let entries = entries
+                    .into_iter()
+                    .map(|(k, v)| (k, Literal::Str(v)))
+                    .collect();
+                metadata.apply("setup.cfg", key, Some(Literal::Dict(entries)));
+            }
+            _ if section.starts_with("options.") => metadata.skip(
+                "setup.cfg",
+                &format!("[{}]", section),
+                "handled by the build backend",
+            ),
+            _ => {}
+        }
+    }
+}
+
+/// Converts a setup.cfg value into the shape `Metadata::apply` expects for `key`.
+fn cfg_literal(key: &str, v: String) -> Literal {
+    match key.replace('-', "_").as_str() {
+        "project_urls" => Literal::Dict(
+            key_value_lines(&v)
+                .into_iter()
+                .map(|(k, v)| (k, Literal::Str(v)))
+                .collect(),
+        ),
+        _ => Literal::Str(v),
+    }
+}
+
+/// A minimal INI reader: sections of `key = value` pairs, where indented lines
+/// continue the previous value.
+fn parse_ini(content: &str) -> Vec<(String, Vec<(String, String)>)> {
+    let mut sections: Vec<(String, Vec<(String, String)>)> = Vec::new();
+
+    for line in content.lines() {
+        let trimmed = line.trim();
+        if trimmed.is_empty() || trimmed.starts_with('#') || trimmed.starts_with(';') {
+            continue;
+        }
+
+        if trimmed.starts_with('[') && trimmed.ends_with(']') {
+            sections.push((trimmed[1..trimmed.len() - 1].trim().to_string(), Vec::new()));
+            continue;
+        }
+
+        let Some((_, entries)) = sections.last_mut() else {
+            continue;
+        };
+        let continuation = line.starts_with(char::is_whitespace);
+        match entries.last_mut() {
+            Some((_, v)) if continuation => {
+                v.push('\n');
+                v.push_str(trimmed);
+            }
+            _ => {
+                if let Some((k, v)) = trimmed.split_once(['=', ':']) {
+                    entries.push((k.trim().to_string(), v.trim().to_string()));
+                }
+            }
+        }
+    }
+
+    sections
+}
+
+/// Parses `name = value` lines, as used by entry points and `project_urls`.
+fn key_value_lines(s: &str) -> Vec<(String, String)> {
+    s.lines()
+        .filter_map(|line| line.split_once('='))
+        .map(|(k, v)| (k.trim().to_string(), v.trim().to_string()))
+        .filter(|(k, v)| !k.is_empty() && !v.is_empty())
+        .collect()
+}
+
+fn split_commas(items: &[String]) -> Vec<String> {
+    items
+        .iter()
+        .flat_map(|item| item.split(','))
+        .map(str::trim)
+        .filter(|item| !item.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+/// Reads the keyword arguments of the `setup(...)` call in a setup.py file.
+fn read_setup_py(content: &str, metadata: &mut Metadata) -> io::Result<()> {
+    let tokens = tokenize(content);
+    let call = tokens.windows(3).position(|w| {
+        matches!(&w[1], Token::Name(n) if n == "setup")
+            && matches!(w[2], Token::Op('('))
+            && !matches!(&w[0], Token::Name(n) if n == "def")
+    });
+    let start = match call {
+        Some(index) => index + 3,
+        None if matches!(tokens.first(), Some(Token::Name(n)) if n == "setup") => 2,
+        None => {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "Could not find a setup(...) call in setup.py.",
+            ))
+        }
+    };
+
+    let mut parser = Parser { tokens, pos: start };
+    loop {
+        match parser.peek() {
+            None | Some(Token::Op(')')) => break,
+            Some(Token::Op(',')) => parser.pos += 1,
+            Some(Token::Name(key)) if matches!(parser.peek_at(1), Some(Token::Op('='))) => {
+                let key = key.clone();
+                parser.pos += 2;
+                let literal = parser.parse_expr();
+                metadata.apply("setup.py", &key, literal);
+            }
+            _ => {
+                let before = parser.pos;
+                parser.skip_expr();
+                if parser.pos == before {
+                    parser.pos += 1;
+                } else {
+                    metadata.skip("setup.py", "positional or ** argument", "not supported");
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+#[derive(Clone)]
+enum Token {
+    Str(String),
+    Name(String),
+    Num(String),
+    Op(char),
+    /// A token we cannot evaluate statically, such as an f-string.
+    Other,
+}
+
+/// Splits Python source into the tokens needed to read literal arguments.
+fn tokenize(source: &str) -> Vec<Token> {
+    let chars: Vec<char> = source.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+        if c.is_whitespace() || c == '\\' {
+            i += 1;
+        } else if c == '#' {
+            while i < chars.len() && chars[i] != '\n' {
+                i += 1;
+            }
+        } else if c.is_alphabetic() || c == '_' {
+            let start = i;
+            while i < chars.len() && (chars[i].is_alphanumeric() || chars[i] == '_') {
+                i += 1;
+            }
+            let word: String = chars[start..i].iter().collect();
+            let is_prefix = word.len() <= 2 && word.chars().all(|c| "rRbBuUfF".contains(c));
+            if is_prefix && i < chars.len() && (chars[i] == '"' || chars[i] == '\'') {
+                let raw = word.contains(['r', 'R']);
+                let (text, end) = read_string(&chars, i, raw);
+                i = end;
+                if word.contains(['f', 'F', 'b', 'B']) {
+                    tokens.push(Token::Other);
+                } else {
+                    tokens.push(Token::Str(text));
+                }
+            } else {
+                tokens.push(Token::Name(word));
+            }
+        } else if c == '"' || c == '\'' {
+            let (text, end) = read_string(&chars, i, false);
+            tokens.push(Token::Str(text));
+            i = end;
+        } else if c.is_ascii_digit() {
+            let start = i;
+            while i < chars.len() && (chars[i].is_ascii_alphanumeric() || chars[i] == '.') {
+                i += 1;
+            }
+            tokens.push(Token::Num(chars[start..i].iter().collect()));
+        } else {
+            tokens.push(Token::Op(c));
+            i += 1;
+        }
+    }
+
+    tokens
+}
+
+/// Reads a string literal starting at the quote at `start`.
+/// Returns its value and the index just past the closing quote.
+fn read_string(chars: &[char], start: usize, raw: bool) -> (String, usize) {
+    let quote = chars[start];
+    let triple = chars.get(start + 1) == Some(&quote) && chars.get(start + 2) == Some(&quote);
+    let mut i = if triple { start + 3 } else { start + 1 };
+    let mut text = String::new();
+
+    while i < chars.len() {
+        let c = chars[i];
+        if c == '\\' && i + 1 < chars.len() {
+            let next = chars[i + 1];
+            if raw {
+                text.push(c);
+                text.push(next);
+            } else {
+                match next {
+                    'n' => text.push('\n'),
+                    't' => text.push('\t'),
+                    '\n' => {}
+                    other => text.push(other),
+                }
+            }
+            i += 2;
+            continue;
+        }
+        if c == quote {
+            if !triple {
+                return (text, i + 1);
+            }
+            if chars.get(i + 1) == Some(&quote) && chars.get(i + 2) == Some(&quote) {
+                return (text, i + 3);
+            }
+        }
+        text.push(c);
+        i += 1;
+    }
+
+    (text, i)
+}
+
+/// Evaluates literal expressions (strings, lists, tuples, dicts, booleans).
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn peek_at(&self, offset: usize) -> Option<&Token> {
+        self.tokens.get(self.pos + offset)
+    }
+
+    fn at_op(&self, op: char) -> bool {
+        matches!(self.peek(), Some(Token::Op(c)) if *c == op)
+    }
+
+    fn at_terminator(&self) -> bool {
+        match self.peek() {
+            None => true,
+            Some(Token::Op(c)) => matches!(c, ',' | ')' | ']' | '}' | ':'),
+            _ => false,
+        }
+    }
+
+    /// Parses one expression. Returns `None` if it is not a plain literal,
+    /// in which case the whole expression is skipped.
+    fn parse_expr(&mut self) -> Option<Literal> {
+        let start = self.pos;
+        match self.parse_primary() {
+            Some(literal) if self.at_terminator() => Some(literal),
+            _ => {
+                self.pos = start;
+                self.skip_expr();
+                None
+            }
+        }
+    }
+
+    fn parse_primary(&mut self) -> Option<Literal> {
+        let token = self.peek()?.clone();
+        self.pos += 1;
+        match token {
+            Token::Str(first) => {
+                // Adjacent literals and `+` both concatenate: `"a" "b"`, `"a" + "b"`
+                let mut text = first;
+                loop {
+                    match (self.peek(), self.peek_at(1)) {
+                        (Some(Token::Str(next)), _) => {
+                            text.push_str(next);
+                            self.pos += 1;
+                        }
+                        (Some(Token::Op('+')), Some(Token::Str(next))) => {
+                            text.push_str(next);
+                            self.pos += 2;
+                        }
+                        _ => break,
+                    }
+                }
+                Some(Literal::Str(text))
+            }
+            Token::Num(n) => Some(Literal::Str(n)),
+            Token::Name(n) if n == "True" || n == "False" => Some(Literal::Bool),
+            Token::Op(open @ ('[' | '(')) => {
+                let close = if open == '[' { ']' } else { ')' };
+                let mut items = Vec::new();
+                while !self.at_op(close) {
+                    items.push(self.parse_expr()?);
+                    if self.at_op(',') {
+                        self.pos += 1;
+                    } else if !self.at_op(close) {
+                        return None;
+                    }
+                }
+                self.pos += 1;
+                Some(Literal::List(items))
+            }
+            Token::Op('{') => {
+                let mut entries = Vec::new();
+                while !self.at_op('}') {
+                    let Literal::Str(key) = self.parse_expr()? else {
+                        return None;
+                    };
+                    if !self.at_op(':') {
+                        return None;
+                    }
+                    self.pos += 1;
+                    entries.push((key, self.parse_expr()?));
+                    if self.at_op(',') {
+                        self.pos += 1;
+                    } else if !self.at_op('}') {
+                        return None;
+                    }
+                }
+                self.pos += 1;
+                Some(Literal::Dict(entries))
+            }
+            _ => None,
+        }
+    }
+
+    /// Advances past the current expression, up to the next `,` or closing bracket.
+    fn skip_expr(&mut self) {
+        let mut depth = 0usize;
+        while let Some(token) = self.peek() {
+            match token {
+                Token::Op('(' | '[' | '{') => depth += 1,
+                Token::Op(')' | ']' | '}') if depth == 0 => return,
+                Token::Op(')' | ']' | '}') => depth -= 1,
+                Token::Op(',') if depth == 0 => return,
+                _ => {}
+            }
+            self.pos += 1;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn from_setup_py(source: &str) -> Metadata {
+        let mut metadata = Metadata::default();
+        read_setup_py(source, &mut metadata).unwrap();
+        metadata
+    }
+
+    fn from_setup_cfg(source: &str) -> Metadata {
+        let mut metadata = Metadata::default();
+        read_setup_cfg(source, &mut metadata);
+        metadata
+    }
+
+    #[test]
+    fn setup_py_literals() {
+        let metadata = from_setup_py(
+            r#"
+from setuptools import setup
+
+setup(
+    name="demo",  # the distribution name
+    version='1.0',
+    description="A " "short" ' description',
+    license="MIT",
+    install_requires=["requests>=2", 'click'],
+    extras_require={"dev": ("pytest",)},
+    entry_points={"console_scripts": ["demo = demo.cli:main"]},
+    zip_safe=False,
+)
+"#,
+        );
+        assert_eq!(metadata.name.as_deref(), Some("demo"));
+        assert_eq!(metadata.version.as_deref(), Some("1.0"));
+        assert_eq!(metadata.description.as_deref(), Some("A short description"));
+        assert_eq!(metadata.dependencies, ["requests>=2", "click"]);
+        assert_eq!(
+            metadata.optional_dependencies,
+            [("dev".to_string(), vec!["pytest".to_string()])]
+        );
+        assert_eq!(
+            metadata.entry_points,
+            [(
+                "console_scripts".to_string(),
+                vec![("demo".to_string(), "demo.cli:main".to_string())]
+            )]
+        );
+        assert_eq!(
+            metadata.skipped,
+            ["setup.py: zip_safe (handled by the build backend)"]
+        );
+    }
+
+    #[test]
+    fn setup_py_concatenation_with_plus() {
+        let metadata = from_setup_py("setup(url='https://' + 'example.org', keywords='a b')");
+        assert_eq!(
+            metadata.urls,
+            [("Homepage".to_string(), "https://example.org".to_string())]
+        );
+        assert_eq!(metadata.keywords, ["a", "b"]);
+    }
+
+    #[test]
+    fn setup_py_non_literals_are_reported() {
+        let metadata = from_setup_py(
+            r#"
+import os
+VERSION = "1.0"
+setup(
+    name="demo",
+    version=VERSION,
+    long_description=open("README.md").read(),
+    description=f"demo {VERSION}",
+    **extra,
+)
+"#,
+        );
+        assert_eq!(metadata.name.as_deref(), Some("demo"));
+        assert_eq!(metadata.version, None);
+        assert_eq!(
+            metadata.skipped,
+            [
+                "setup.py: version (value is not a static literal)",
+                "setup.py: long_description (value is not a static literal)",
+                "setup.py: description (value is not a static literal)",
+                "setup.py: positional or ** argument (not supported)",
+            ]
+        );
+    }
+
+    #[test]
+    fn setup_py_without_setup_call() {
+        let mut metadata = Metadata::default();
+        assert!(read_setup_py("def setup(): pass\n", &mut metadata).is_err());
+    }
+
+    #[test]
+    fn setup_cfg_multi_line_values() {
+        let metadata = from_setup_cfg(
+            "[metadata]
+name = demo
+version = attr: demo.__version__
+classifiers =
+    Programming Language :: Python :: 3
+    # a comment
+    Operating System :: OS Independent
+project_urls =
+    Source = https://example.org/src
+    Issues = https://example.org/issues
+
+[options]
+install_requires =
+    requests>=2
+    click
+packages = find:
+
+[options.extras_require]
+dev =
+    pytest
+    ruff
+
+[options.entry_points]
+console_scripts =
+    demo = demo.cli:main
+",
+        );
+        assert_eq!(metadata.name.as_deref(), Some("demo"));
+        assert_eq!(metadata.version, None);
+        assert_eq!(
+            metadata.classifiers,
+            [
+                "Programming Language :: Python :: 3",
+                "Operating System :: OS Independent"
+            ]
+        );
+        assert_eq!(
+            metadata.urls,
+            [
+                ("Source".to_string(), "https://example.org/src".to_string()),
+                (
+                    "Issues".to_string(),
+                    "https://example.org/issues".to_string()
+                ),
+            ]
+        );
+        assert_eq!(metadata.dependencies, ["requests>=2", "click"]);
+        assert_eq!(
+            metadata.optional_dependencies,
+            [(
+                "dev".to_string(),
+                vec!["pytest".to_string(), "ruff".to_string()]
+            )]
+        );
+        assert_eq!(
+            metadata.entry_points,
+            [(
+                "console_scripts".to_string(),
+                vec![("demo".to_string(), "demo.cli:main".to_string())]
+            )]
+        );
+        assert_eq!(
+            metadata.skipped,
+            [
+                "setup.cfg: version (dynamic values are not supported)",
+                "setup.cfg: packages (handled by the build backend)",
+            ]
+        );
+    }
+
+    #[test]
+    fn setup_py_takes_precedence_over_setup_cfg() {
+        let cfg = from_setup_cfg(
+            "[metadata]
+name = demo
+description = from setup.cfg
+classifiers = Programming Language :: Python :: 3
+project_urls =
+    Source = https://example.org/cfg
+[options]
+install_requires =
+    requests>=2
+",
+        );
+        let py = from_setup_py(
+            r#"setup(
+    description="from setup.py",
+    classifiers=["Programming Language :: Python :: 3"],
+    install_requires=["requests>=2", "click"],
+    project_urls={"Source": "https://example.org/py", "Docs": "https://example.org/docs"},
+)"#,
+        );
+        let metadata = cfg.merge(py);
+        assert_eq!(metadata.name.as_deref(), Some("demo"));
+        assert_eq!(metadata.description.as_deref(), Some("from setup.py"));
+        assert_eq!(
+            metadata.classifiers,
+            ["Programming Language :: Python :: 3"]
+        );
+        assert_eq!(metadata.dependencies, ["requests>=2", "click"]);
+        assert_eq!(
+            metadata.urls,
+            [
+                ("Source".to_string(), "https://example.org/py".to_string()),
+                ("Docs".to_string(), "https://example.org/docs".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn requirements_files() {
+        let dir = std::env::temp_dir().join(format!("hajime-migrate-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(
+            dir.join("requirements.txt"),
+            "# pinned\nrequests>=2\n\nclick\n",
+        )
+        .unwrap();
+        fs::write(dir.join("requirements-dev.txt"), "pytest\n").unwrap();
+
+        let mut metadata = Metadata::default();
+        let files = read_requirements_files(&dir, &mut metadata).unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(files, ["requirements-dev.txt", "requirements.txt"]);
+        assert_eq!(metadata.dependencies, ["requests>=2", "click"]);
+        assert_eq!(
+            metadata.optional_dependencies,
+            [("dev".to_string(), vec!["pytest".to_string()])]
+        );
+    }
+}
@@ -0,0 +1,544 @@
+use crate::pypi::{self, Lookup};
+use std::io;
+
+/// Popular distributions that new project names are compared against.
+const POPULAR_PACKAGES: &str = include_str!("../templates/popular-packages.txt");
+
+/// Python keywords, including the soft keywords `match` and `case`.
+const KEYWORDS: &[&str] = &[
+    "False", "None", "True", "and", "as", "assert", "async", "await", "break", "case", "class",
+    "continue", "def", "del", "elif", "else", "except", "finally", "for", "from", "global", "if",
+    "import", "in", "is", "lambda", "match", "nonlocal", "not", "or", "pass", "raise", "return",
+    "try", "while", "with", "yield",
+];
+
+/// Top-level standard library modules that a project package would shadow.
+const STDLIB_MODULES: &[&str] = &[
+    "abc",
+    "aifc",
+    "antigravity",
+    "argparse",
+    "array",
+    "ast",
+    "asynchat",
+    "asyncio",
+    "asyncore",
+    "atexit",
+    "audioop",
+    "base64",
+    "bdb",
+    "binascii",
+    "bisect",
+    "builtins",
+    "bz2",
+    "calendar",
+    "cgi",
+    "cgitb",
+    "chunk",
+    "cmath",
+    "cmd",
+    "code",
+    "codecs",
+    "codeop",
+    "collections",
+    "colorsys",
+    "compileall",
+    "concurrent",
+    "configparser",
+    "contextlib",
+    "contextvars",
+    "copy",
+    "copyreg",
+    "cprofile",
+    "crypt",
+    "csv",
+    "ctypes",
+    "curses",
+    "dataclasses",
+    "datetime",
+    "dbm",
+    "decimal",
+    "difflib",
+    "dis",
+    "distutils",
+    "doctest",
+    "email",
+    "encodings",
+    "ensurepip",
+    "enum",
+    "errno",
+    "faulthandler",
+    "fcntl",
+    "filecmp",
+    "fileinput",
+    "fnmatch",
+    "fractions",
+    "ftplib",
+    "functools",
+    "gc",
+    "genericpath",
+    "getopt",
+    "getpass",
+    "gettext",
+    "glob",
+    "graphlib",
+    "grp",
+    "gzip",
+    "hashlib",
+    "heapq",
+    "hmac",
+    "html",
+    "http",
+    "idlelib",
+    "imaplib",
+    "imghdr",
+    "imp",
+    "importlib",
+    "inspect",
+    "io",
+    "ipaddress",
+    "itertools",
+    "json",
+    "keyword",
+    "lib2to3",
+    "linecache",
+    "locale",
+    "logging",
+    "lzma",
+    "mailbox",
+    "mailcap",
+    "marshal",
+    "math",
+    "mimetypes",
+    "mmap",
+    "modulefinder",
+    "msilib",
+    "msvcrt",
+    "multiprocessing",
+    "netrc",
+    "nis",
+    "nntplib",
+    "nt",
+    "ntpath",
+    "nturl2path",
+    "numbers",
+    "opcode",
+    "operator",
+    "optparse",
+    "os",
+    "ossaudiodev",
+    "pathlib",
+    "pdb",
+    "pickle",
+    "pickletools",
+    "pipes",
+    "pkgutil",
+    "platform",
+    "plistlib",
+    "poplib",
+    "posix",
+    "posixpath",
+    "pprint",
+    "profile",
+    "pstats",
+    "pty",
+    "pwd",
+    "py_compile",
+    "pyclbr",
+    "pydoc",
+    "pydoc_data",
+    "pyexpat",
+    "queue",
+    "quopri",
+    "random",
+    "re",
+    "readline",
+    "reprlib",
+    "resource",
+    "rlcompleter",
+    "runpy",
+    "sched",
+    "secrets",
+    "select",
+    "selectors",
+    "shelve",
+    "shlex",
+    "shutil",
+    "signal",
+    "site",
+    "smtpd",
+    "smtplib",
+    "sndhdr",
+    "socket",
+    "socketserver",
+    "spwd",
+    "sqlite3",
+    "sre_compile",
+    "sre_constants",
+    "sre_parse",
+    "ssl",
+    "stat",
+    "statistics",
+    "string",
+    "stringprep",
+    "struct",
+    "subprocess",
+    "sunau",
+    "symtable",
+    "sys",
+    "sysconfig",
+    "syslog",
+    "tabnanny",
+    "tarfile",
+    "telnetlib",
+    "tempfile",
+    "termios",
+    "test",
+    "textwrap",
+    "this",
+    "threading",
+    "time",
+    "timeit",
+    "tkinter",
+    "token",
+    "tokenize",
+    "tomllib",
+    "trace",
+    "traceback",
+    "tracemalloc",
+    "tty",
+    "turtle",
+    "turtledemo",
+    "types",
+    "typing",
+    "unicodedata",
+    "unittest",
+    "urllib",
+    "uu",
+    "uuid",
+    "venv",
+    "warnings",
+    "wave",
+    "weakref",
+    "webbrowser",
+    "winreg",
+    "winsound",
+    "wsgiref",
+    "xdrlib",
+    "xml",
+    "xmlrpc",
+    "zipapp",
+    "zipfile",
+    "zipimport",
+    "zlib",
+    "zoneinfo",
+];
+
+fn is_separator(c: char) -> bool {
+    matches!(c, '-' | '_' | '.')
+}
+
+/// Checks that `name` is a valid PEP 508 distribution name: ASCII letters, digits,
+/// `.`, `_` and `-`, starting and ending with a letter or digit.
+pub fn validate_project_name(name: &str) -> io::Result<()> {
+    let valid = name.starts_with(|c: char| c.is_ascii_alphanumeric())
+        && name.ends_with(|c: char| c.is_ascii_alphanumeric())
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || is_separator(c));
+    if valid {
+        return Ok(());
+    }
+
+    let mut message = format!(
+        "Invalid project name '{}': names may only contain ASCII letters, digits, '.', '_' and '-', and must start and end with a letter or digit.",
+        name
+    );
+    let suggestion = suggest_project_name(name);
+    if !suggestion.is_empty() {
+        message.push_str(&format!(" Try '{}'.", suggestion));
+    }
+    Err(io::Error::new(io::ErrorKind::InvalidInput, message))
+}
+
+/// Replaces invalid characters with `-` and trims separators from both ends.
+fn suggest_project_name(name: &str) -> String {
+    let replaced: String = name
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || is_separator(c) {
+                c
+            } else {
+                '-'
+            }
+        })
+        .collect();
+    normalize(replaced.trim_matches(|c: char| !c.is_ascii_alphanumeric()))
+}
+
+/// Normalizes a distribution name as in PEP 503: lowercase, with runs of `-`, `_`
+/// and `.` collapsed to a single `-`.
+pub fn normalize(name: &str) -> String {
+    let mut normalized = String::with_capacity(name.len());
+    for c in name.chars() {
+        if is_separator(c) {
+            if !normalized.ends_with('-') {
+                normalized.push('-');
+            }
+        } else {
+            normalized.push(c.to_ascii_lowercase());
+        }
+    }
+    normalized
+}
+
+/// Returns the import name for `project_name`, or `override_name` if given.
+/// The derived name is the normalized project name with `-` replaced by `_`.
+pub fn import_name(project_name: &str, override_name: Option<&str>) -> io::Result<String> {
+    let name = match override_name {
+        Some(name) => name.to_string(),
+        None => normalize(project_name).replace('-', "_"),
+    };
+    validate_import_name(&name)?;
+    Ok(name)
+}
+
+/// Checks that `name` can be imported and does not shadow a keyword or a standard
+/// library module.
+fn validate_import_name(name: &str) -> io::Result<()> {
+    let invalid = |reason: String, suggestion: String| {
+        Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!(
+                "Invalid import name '{}': {}. Pass --import-name, e.g. --import-name {}.",
+                name, reason, suggestion
+            ),
+        ))
+    };
+
+    if name.is_empty() || !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+        let suggestion = normalize(&suggest_project_name(name)).replace('-', "_");
+        return invalid(
+            "only ASCII letters, digits and '_' are allowed".to_string(),
+            if suggestion.is_empty() {
+                "my_package".to_string()
+            } else {
+                suggestion
+            },
+        );
+    }
+    if name.starts_with(|c: char| c.is_ascii_digit()) {
+        return invalid("it starts with a digit".to_string(), format!("py{}", name));
+    }
+    if KEYWORDS.contains(&name) {
+        return invalid("it is a Python keyword".to_string(), format!("{}_", name));
+    }
+    let lower = name.to_ascii_lowercase();
+    if STDLIB_MODULES.contains(&lower.as_str()) {
+        return invalid(
+            format!("it would shadow the standard library module '{}'", lower),
+            format!("py{}", name),
+        );
+    }
+    Ok(())
+}
+
+/// Popular packages that `name` is easily confused with: the same name once
+/// separators are ignored, or within a small edit distance.
+pub fn confusables(name: &str) -> Vec<&'static str> {
+    let normalized = normalize(name);
+    let squashed = normalized.replace('-', "");
+    POPULAR_PACKAGES
+        .lines()
+        .map(str::trim)
+        .filter(|p| !p.is_empty() && !p.starts_with('#'))
+        .filter(|p| *p != normalized)
+        .filter(|p| {
+            if p.replace('-', "") == squashed {
+                return true;
+            }
+            let max_distance = match p.len() {
+                0..=3 => 0,
+                4..=9 => 1,
+                _ => 2,
+            };
+            edit_distance(&normalized, p) <= max_distance
+        })
+        .collect()
+}
+
+/// Edit distance between two ASCII strings, counting insertions, deletions,
+/// substitutions and transpositions of adjacent characters.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let (a, b) = (a.as_bytes(), b.as_bytes());
+    let mut d = vec![vec![0; b.len() + 1]; a.len() + 1];
+    for (i, row) in d.iter_mut().enumerate() {
+        row[0] = i;
+    }
+    for (j, cell) in d[0].iter_mut().enumerate() {
+        *cell = j;
+    }
+    for i in 1..=a.len() {
+        for j in 1..=b.len() {
+            let cost = usize::from(a[i - 1] != b[j - 1]);
+            d[i][j] = (d[i - 1][j] + 1)
+                .min(d[i][j - 1] + 1)
+                .min(d[i - 1][j - 1] + cost);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                d[i][j] = d[i][j].min(d[i - 2][j - 2] + 1);
+            }
+        }
+    }
+    d[a.len()][b.len()]
+}
+
+/// Warns if `project_name` is already taken on the index or confusable with a
+/// popular package, and returns whether it is taken. An unreachable index is
+/// reported and otherwise ignored.
+pub fn check_availability(project_name: &str, index_url: &str) -> bool {
+    let normalized = normalize(project_name);
+    let taken = match pypi::lookup(index_url, &normalized) {
+        Lookup::Found(json) => {
+            println!(
+                "Warning: '{}' is already taken on {} (latest version {}).",
+                normalized,
+                index_url,
+                json["info"]["version"].as_str().unwrap_or("unknown")
+            );
+            true
+        }
+        Lookup::NotFound => {
+            println!("'{}' is available on {}.", normalized, index_url);
+            false
+        }
+        Lookup::Unavailable(reason) => {
+            println!(
+                "Skipping the availability check; {} is unreachable ({}).",
+                index_url, reason
+            );
+            false
+        }
+    };
+
+    for package in confusables(project_name) {
+        println!(
+            "Warning: '{}' is easily confused with the popular package '{}'.",
+            project_name, package
+        );
+    }
+    taken
+}
+
+/// Checks a project name: validity, normalized and import names, availability
+/// on the index and confusable popular packages. Errors if the name is invalid
+/// or already taken, so the check can gate scripts.
+///
+/// # Arguments
+/// * `name` - The project name to check.
+/// * `index_url` - JSON API base URL (defaults to `$HAJIME_INDEX_URL`, then PyPI).
+pub fn check_name(name: &str, index_url: Option<&str>) -> io::Result<()> {
+    validate_project_name(name)?;
+    println!("Normalized name: {}", normalize(name));
+    match import_name(name, None) {
+        Ok(import_name) => println!("Import name: {}", import_name),
+        Err(e) => println!("Warning: {}", e),
+    }
+    let index_url = pypi::index_url(index_url, None);
+    if check_availability(name, &index_url) {
+        return Err(io::Error::new(
+            io::ErrorKind::AlreadyExists,
+            format!("'{}' is already taken on {}.", normalize(name), index_url),
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pypi::tests::stand_in_index;
+
+    #[test]
+    fn valid_project_names() {
+        for name in ["demo", "my-project", "My_Project.2", "a", "0x"] {
+            assert!(validate_project_name(name).is_ok(), "{}", name);
+        }
+    }
+
+    #[test]
+    fn invalid_project_names_get_a_suggestion() {
+        let message = |name: &str| validate_project_name(name).unwrap_err().to_string();
+        assert!(message("my project").ends_with("Try 'my-project'."));
+        assert!(message("-demo-").ends_with("Try 'demo'."));
+        assert!(message("café").ends_with("Try 'caf'."));
+        assert!(!message("!!!").contains("Try"));
+        assert!(validate_project_name("").is_err());
+    }
+
+    #[test]
+    fn normalize_follows_pep_503() {
+        assert_eq!(normalize("My_Project"), "my-project");
+        assert_eq!(normalize("a.-_b"), "a-b");
+        assert_eq!(normalize("Django"), "django");
+    }
+
+    #[test]
+    fn import_name_derivation() {
+        assert_eq!(
+            import_name("My-Project.Core", None).unwrap(),
+            "my_project_core"
+        );
+        assert_eq!(import_name("demo", Some("Demo2")).unwrap(), "Demo2");
+    }
+
+    #[test]
+    fn import_name_rejections() {
+        let message = |name: &str| import_name(name, None).unwrap_err().to_string();
+        assert!(message("3d-tools").contains("starts with a digit"));
+        assert!(message("3d-tools").ends_with("--import-name py3d_tools."));
+        assert!(message("lambda").contains("is a Python keyword"));
+        assert!(message("lambda").ends_with("--import-name lambda_."));
+        assert!(message("Json").contains("shadow the standard library module 'json'"));
+        assert!(message("Json").ends_with("--import-name pyjson."));
+        let message = import_name("demo", Some("my-pkg")).unwrap_err().to_string();
+        assert!(message.ends_with("--import-name my_pkg."));
+    }
+
+    #[test]
+    fn confusable_names() {
+        assert_eq!(confusables("reqeusts"), ["requests"]);
+        assert_eq!(confusables("python_dateutil"), Vec::<&str>::new());
+        assert_eq!(confusables("pythondateutil"), ["python-dateutil"]);
+        assert_eq!(confusables("nunpy"), ["numpy"]);
+        assert!(confusables("requests").is_empty());
+        // Short names must match exactly once separators are ignored
+        assert!(!confusables("sex").contains(&"six"));
+    }
+
+    #[test]
+    fn edit_distances() {
+        assert_eq!(edit_distance("requests", "requests"), 0);
+        assert_eq!(edit_distance("reqeusts", "requests"), 1);
+        assert_eq!(edit_distance("numpy", "nunpy"), 1);
+        assert_eq!(edit_distance("attrs", "attr"), 1);
+        assert_eq!(edit_distance("kitten", "sitting"), 3);
+        assert_eq!(edit_distance("", "abc"), 3);
+    }
+
+    #[test]
+    fn check_name_fails_for_taken_names() {
+        let index = stand_in_index(&["taken-name"]);
+        let err = check_name("Taken_Name", Some(&index)).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::AlreadyExists);
+        assert!(check_name("free-name", Some(&index)).is_ok());
+    }
+
+    #[test]
+    fn availability_against_stand_in_index() {
+        let index = stand_in_index(&["taken-name"]);
+        assert!(check_availability("taken.name", &index));
+        assert!(!check_availability("free-name", &index));
+        // An unreachable index does not block
+        assert!(!check_availability("taken-name", "http://127.0.0.1:1"));
+    }
+}
@@ -1,197 +1,659 @@
-use crate::helpers::{is_git_installed, is_uv_installed, to_snake_case};
-use std::fs::{self, File};
-use std::io::Write;
-use std::path::Path;
+use crate::backend::Backend;
+use crate::ci::CiProvider;
+use crate::cli::CliFramework;
+use crate::git;
+use crate::gitignore::{self, Preset, GITIGNORE};
+use crate::helpers::{confirm, is_git_installed, is_uv_installed, timestamp};
+use crate::hooks::{self, PRE_COMMIT_CONFIG};
+use crate::layout::Layout;
+use crate::license::{self, License, LICENSE_FILE};
+use crate::naming;
+use crate::pypi;
+use crate::pyproject;
+use crate::template::{Template, TemplateContext, DEFAULT_TEMPLATE, RUST_TEMPLATE};
+use crate::tools;
+use crate::workspace;
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
 use std::process::Command;
+use toml_edit::value;
+
+/// Minimum Python version written to `requires-python` in new projects.
+pub const DEFAULT_PYTHON_VERSION: &str = "3.9";
+
+/// Message of the commit made by `--initial-commit`, in Conventional Commits style.
+const INITIAL_COMMIT_MESSAGE: &str = "chore: initial project skeleton";
+
+/// PEP 561 marker file for packages that ship type information.
+const PY_TYPED: &str = "py.typed";
+
+/// Options controlling how `hajime new` generates a project.
+#[derive(Clone, Default)]
+pub struct NewOptions {
+    /// Replace an existing project, moving it to a timestamped backup first.
+    pub force: bool,
+    /// Print what would be created without touching the file system.
+    pub dry_run: bool,
+    /// Skip the confirmation prompt for `force`.
+    pub yes: bool,
+    /// A built-in template name or a template directory (defaults to `default`).
+    pub template: Option<String>,
+    /// Import name of the package; derived from the project name when unset.
+    pub import_name: Option<String>,
+    /// JSON API base URL used to check whether the name is taken.
+    pub index_url: Option<String>,
+    /// Where the import package is placed.
+    pub layout: Layout,
+    /// Build backend; when unset, the template's backend (or hatchling) is kept.
+    pub backend: Option<Backend>,
+    /// Scaffold a mixed Rust/Python project built with maturin and PyO3.
+    pub rust: bool,
+    /// Project description; defaults to "A Python project named <name>".
+    pub description: Option<String>,
+    /// Author name; defaults to git's `user.name`, then `$USER`.
+    pub author: Option<String>,
+    /// Author email; defaults to git's `user.email`.
+    pub author_email: Option<String>,
+    /// SPDX license expression for `[project] license`.
+    pub license: Option<String>,
+    /// Minimum Python version for `requires-python`, e.g. `3.10`.
+    pub python_version: Option<String>,
+    /// Register the example function as a console script in `[project.scripts]`.
+    pub entry_point: bool,
+    /// Generate a command-line interface with this framework and register it as a console script.
+    pub cli: Option<CliFramework>,
+    /// Configure mypy and mark the package as typed with `py.typed`.
+    pub mypy: bool,
+    /// Use the strict lint, test and type-checking profile; implies `mypy`.
+    pub strict: bool,
+    /// Generate a CI workflow for this service.
+    pub ci: Option<CiProvider>,
+    /// Write `.pre-commit-config.yaml` and install a native pre-commit hook.
+    pub pre_commit: bool,
+    /// Commit the generated skeleton.
+    pub initial_commit: bool,
+    /// Name of the default branch; git's default when unset.
+    pub branch: Option<String>,
+    /// URL of the `origin` remote.
+    pub remote: Option<String>,
+    /// `.gitignore` presets from `--gitignore` or the `gitignore` setting;
+    /// [`Preset::DEFAULT`] when empty.
+    pub gitignore: Vec<Preset>,
+    /// Create a uv workspace root instead of a package.
+    pub workspace: bool,
+}
+
+impl NewOptions {
+    /// Whether the project is a mixed Rust/Python project.
+    /// A maturin backend needs a Rust crate, so it implies `--rust`.
+    pub fn is_rust(&self) -> bool {
+        self.rust || self.backend == Some(Backend::Maturin)
+    }
+
+    /// Whether mypy is configured.
+    pub fn mypy_enabled(&self) -> bool {
+        self.mypy || self.strict
+    }
+
+    /// Where the import package is placed, relative to the project root.
+    /// Rust projects keep their Python sources in `python/`.
+    pub fn package_dir(&self, module_name: &str) -> String {
+        if self.is_rust() {
+            format!("python/{}", module_name)
+        } else {
+            self.layout.package_dir(module_name)
+        }
+    }
+
+    /// The `.gitignore` presets: always `python`, plus `rust` for Rust projects.
+    pub fn gitignore_presets(&self) -> Vec<Preset> {
+        let chosen: &[Preset] = if self.gitignore.is_empty() {
+            &Preset::DEFAULT
+        } else {
+            &self.gitignore
+        };
+        let mut presets = vec![Preset::Python];
+        if self.is_rust() {
+            presets.push(Preset::Rust);
+        }
+        for preset in chosen {
+            if !presets.contains(preset) {
+                presets.push(*preset);
+            }
+        }
+        presets
+    }
+
+    /// The author name, falling back to git's `user.name` as seen from `dir`, then `$USER`.
+    pub fn author_name(&self, dir: &Path) -> String {
+        self.author
+            .clone()
+            .or_else(|| git::config_value("user.name", dir))
+            .or_else(|| std::env::var("USER").ok())
+            .unwrap_or_default()
+    }
+
+    /// The author email, falling back to git's `user.email` as seen from `dir`.
+    pub fn author_email(&self, dir: &Path) -> String {
+        self.author_email
+            .clone()
+            .or_else(|| git::config_value("user.email", dir))
+            .unwrap_or_default()
+    }
+
+    /// The project description, falling back to a generic one.
+    pub fn description(&self, project_name: &str) -> String {
+        self.description
+            .clone()
+            .unwrap_or_else(|| format!("A Python project named {}", project_name))
+    }
+}
+
+/// Formats the `authors` array of `pyproject.toml` as an inline TOML value.
+fn authors_array(name: &str, email: &str) -> String {
+    let mut fields = Vec::new();
+    if !name.is_empty() {
+        fields.push(format!("name = {}", toml_edit::Value::from(name)));
+    }
+    if !email.is_empty() {
+        fields.push(format!("email = {}", toml_edit::Value::from(email)));
+    }
+
+    if fields.is_empty() {
+        "[]".to_string()
+    } else {
+        format!("[{{ {} }}]", fields.join(", "))
+    }
+}
+
+/// Checks that `version` is a `3.X` Python version.
+pub fn validate_python_version(version: &str) -> io::Result<()> {
+    let valid = match version.split_once('.') {
+        Some((major, minor)) => major == "3" && minor.parse::<u32>().is_ok(),
+        None => false,
+    };
+    if valid {
+        Ok(())
+    } else {
+        Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!(
+                "Invalid minimum Python version '{}' (expected e.g. 3.10).",
+                version
+            ),
+        ))
+    }
+}
 
 /// Creates a new Python project skeleton
 ///
 /// # Arguments
 /// * `project_name` - The name of the project to create.
-/// * `force` - A boolean indicating whether to overwrite an existing project.
-pub fn create_project(project_name: &str, force: bool) -> std::io::Result<()> {
-    let base_path = Path::new(project_name);
-    let source_name = to_snake_case(project_name);
-    let source_path = base_path.join(&source_name);
-    let venv_path: std::path::PathBuf = base_path.join(".venv");
+/// * `options` - Generation options, see [`NewOptions`].
+pub fn create_project(parent: &Path, project_name: &str, options: &NewOptions) -> io::Result<()> {
+    naming::validate_project_name(project_name)?;
+    if options.workspace {
+        return workspace::create_workspace(parent, project_name, options);
+    }
 
+    // Inside a uv workspace, the project becomes a member package
+    let workspace_root = workspace::find_root(parent);
+    let base_path = match &workspace_root {
+        Some(root) => workspace::member_path(root, project_name)?,
+        None => parent.join(project_name),
+    };
+    let base_path = base_path.as_path();
+    let source_name = naming::import_name(project_name, options.import_name.as_deref())?;
+    if let Some(version) = &options.python_version {
+        validate_python_version(version)?;
+    }
+    if let Some(license) = &options.license {
+        license::validate_expression(license)?;
+    }
+
+    let rust = options.is_rust();
+    if rust {
+        if options.layout == Layout::Src {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "The src layout is not available for Rust projects; Python sources go in python/.",
+            ));
+        }
+        if options.backend.is_some_and(|b| b != Backend::Maturin) {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "Rust projects are built with maturin; --backend must be maturin.",
+            ));
+        }
+    }
+
+    let default_template = if rust {
+        RUST_TEMPLATE
+    } else {
+        DEFAULT_TEMPLATE
+    };
+    let template = Template::resolve(options.template.as_deref().unwrap_or(default_template))?;
+    let cli_template = options.cli.map(CliFramework::template);
+
+    let context = template_context(parent, project_name, &source_name, options);
     // Check if the project directory already exists
+    let mut backup_path = None;
     if base_path.exists() {
-        if !force {
-            eprintln!(
-                "Error: A project with the name '{}' already exists. Use the --force flag to overwrite.",
+        if !options.force {
+            return Err(io::Error::new(
+                io::ErrorKind::AlreadyExists,
+                format!(
+                    "A project with the name '{}' already exists. Use the --force flag to overwrite.",
+                    project_name
+                ),
+            ));
+        }
+
+        if base_path.join(".git").exists()
+            && is_git_installed()
+            && git::has_uncommitted_changes(base_path)?
+        {
+            return Err(io::Error::other(format!(
+                "'{}' is a Git repository with uncommitted changes. Commit or stash them before using --force.",
                 project_name
+            )));
+        }
+        backup_path =
+            Some(base_path.with_file_name(format!("{}.backup-{}", project_name, timestamp())));
+    }
+
+    if options.dry_run {
+        if let Some(backup_path) = &backup_path {
+            println!(
+                "Would move the existing '{}' to '{}'.",
+                project_name,
+                backup_path.display()
             );
+        }
+        println!("Would create project '{}':", project_name);
+        let mut paths = template.paths(&context);
+        if !paths.iter().any(|p| p == Path::new(GITIGNORE)) {
+            paths.push(PathBuf::from(GITIGNORE));
+        }
+        if let Some(cli_template) = &cli_template {
+            paths.extend(cli_template.paths(&context));
+        }
+        if let Some(ci) = options.ci {
+            paths.push(ci.path());
+        }
+        if options.pre_commit {
+            paths.push(PathBuf::from(PRE_COMMIT_CONFIG));
+        }
+        if options.mypy_enabled() {
+            paths.push(Path::new(&options.package_dir(&source_name)).join(PY_TYPED));
+        }
+        if options.license.as_deref().and_then(License::find).is_some() {
+            paths.push(PathBuf::from(LICENSE_FILE));
+        }
+        print_tree(project_name, &paths);
+        return Ok(());
+    }
+
+    // A taken name only warns; the project may not be meant for the index
+    naming::check_availability(
+        project_name,
+        &pypi::index_url(options.index_url.as_deref(), None),
+    );
+
+    if let Some(backup_path) = &backup_path {
+        let prompt = format!(
+            "Move the existing '{}' to '{}' and create a new project?",
+            project_name,
+            backup_path.display()
+        );
+        if !options.yes && !confirm(&prompt)? {
+            println!("Aborted.");
             return Ok(());
-        } else {
-            println!("Warning: Overwriting existing project '{}'.", project_name);
-            fs::remove_dir_all(&base_path)?; // Remove the existing directory if forced
         }
+        fs::rename(base_path, backup_path)?;
+        println!(
+            "Moved the existing '{}' to '{}'.",
+            project_name,
+            backup_path.display()
+        );
     }
 
     // Create the base project directory
-    fs::create_dir_all(&base_path)?;
-
-    // Create the source directory inside the base path with `__init__.py` and `main.py`
-    fs::create_dir_all(&source_path)?;
-
-    // Create `__init__.py` (empty)
-    File::create(source_path.join("__init__.py"))?;
-
-    // Create `main.py` with a "Hello, world!" example
-    let mut main_py = File::create(source_path.join("greet.py"))?;
-    writeln!(main_py, "def hello(name: str = \"world\") -> None:")?;
-    writeln!(main_py, "    print(f\"Hello, {{name}}!\")")?;
-    writeln!(main_py, "\n\nif __name__ == '__main__':")?;
-    writeln!(main_py, "    hello()")?;
-
-    // Create the `tests` folder with `__init__.py` and `test_main.py`
-    let tests_dir = base_path.join("tests");
-    fs::create_dir_all(&tests_dir)?;
-    File::create(tests_dir.join("__init__.py"))?;
-    let mut test_main_py = File::create(tests_dir.join("test_greet.py"))?;
-    writeln!(test_main_py, "from {source_name}.greet import hello")?;
-    writeln!(test_main_py, "\n\ndef test_hello():")?;
-    writeln!(
-        test_main_py,
-        "    hello(\"test\")  # Should print 'Hello, test!'"
-    )?;
-
-    // Create an improved pyproject.toml
-    let mut pyproject = File::create(base_path.join("pyproject.toml"))?;
-    writeln!(
-        pyproject,
-        r#"[build-system]
-requires = ["hatchling"]
-build-backend = "hatchling.build"
-
-[project]
-name = "{project_name}"
-version = "0.1.0"
-description = "A Python project named {project_name}"
-authors = []
-requires-python = ">=3.9.0"
-dependencies = []
-readme = {{file = "README.md", content-type = "text/markdown"}}
-
-[project.optional-dependencies]
-dev = [
-    "ruff",
-    "pytest",
-    "twine", 
-    "maturin",
-]
-
-[tool.setuptools]
-packages = {{find = {{}}}}
-include-package-data = true
-"#
-    )?;
-
-    // Create README.md
-    let mut readme = File::create(base_path.join("README.md"))?;
-    writeln!(readme, "# {}\n\nA new Python project.\n\n", project_name)?;
-    writeln!(
-        readme,
-        "## Installation\n\n```bash\nsource .venv/bin/activate\nuv pip install \".[dev]\"\n```\n",
-    )?;
-
-    // Check if Git is installed, then initialize a Git repository
-    if is_git_installed() {
-        Command::new("git")
-            .arg("init")
-            .current_dir(base_path)
-            .output()
-            .expect("Failed to initialize git repository");
-
-        // Create .gitignore file
-        let mut gitignore = File::create(base_path.join(".gitignore"))?;
-        writeln!(gitignore, "# Byte-compiled / optimized / DLL files")?;
-        writeln!(gitignore, "__pycache__/")?;
-        writeln!(gitignore, "*.py[cod]")?;
-        writeln!(gitignore, "*$py.class")?;
-        writeln!(gitignore, ".pytest_cache")?;
-        writeln!(gitignore, "")?;
-
-        writeln!(gitignore, "# Distribution / packaging")?;
-        writeln!(gitignore, "build/")?;
-        writeln!(gitignore, "dist/")?;
-        writeln!(gitignore, "*.egg-info/")?;
-        writeln!(gitignore, "*.egg")?;
-        writeln!(gitignore, "")?;
-
-        writeln!(gitignore, "# Environments")?;
-        writeln!(gitignore, ".env")?;
-        writeln!(gitignore, ".venv")?;
-        writeln!(gitignore, "env/")?;
-        writeln!(gitignore, "venv/")?;
-        writeln!(gitignore, "")?;
-
-        writeln!(gitignore, "# Jupyter Notebook")?;
-        writeln!(gitignore, ".ipynb_checkpoints")?;
-        writeln!(gitignore, "")?;
-
-        writeln!(gitignore, "# macOS")?;
-        writeln!(gitignore, ".DS_Store")?;
-        writeln!(gitignore, "")?;
-
-        writeln!(gitignore, "# Editor directories and files")?;
-        writeln!(gitignore, ".idea/")?;
-        writeln!(gitignore, ".vscode/")?;
-        writeln!(gitignore, "*.swp")?;
-        writeln!(gitignore, "*.swo")?;
-
-        println!("Initialized Git repository and created .gitignore.");
-    } else {
-        println!("Warning: Git is not installed. Skipping Git repository initialization.");
+    fs::create_dir_all(base_path)?;
+
+    // Render the project skeleton from the template
+    let written = template.render(&context, base_path)?;
+    println!(
+        "Rendered {} files from template '{}'.",
+        written.len(),
+        template.name
+    );
+    if let Some(cli_template) = &cli_template {
+        cli_template.render(&context, base_path)?;
+        println!(
+            "Added a {} command-line interface.",
+            options.cli.unwrap_or_default()
+        );
     }
 
-    if is_uv_installed() {
-        println!("Detected `uv`. Creating virtual environment...");
-        let uv_command = Command::new("uv")
-            .args(&["venv", ".venv"])
-            .current_dir(base_path)
-            .output()
-            .expect("Failed to create virtual environment using `uv`.");
+    gitignore::write(base_path, &options.gitignore_presets())?;
 
-        if !uv_command.status.success() {
-            eprintln!("Error: Failed to create virtual environment using `uv`.");
-        } else {
-            println!("Virtual environment `.venv` created successfully.\n");
+    if options.mypy_enabled() {
+        let marker = base_path
+            .join(options.package_dir(&source_name))
+            .join(PY_TYPED);
+        if !marker.exists() {
+            fs::write(marker, "")?;
         }
+    }
+
+    if let Some(id) = &options.license {
+        write_license(base_path, id, options)?;
+    }
 
-        // Check if the virtual environment exists
-        if venv_path.exists() {
-            println!("To activate the virtual environment, run:");
-            println!("    source .venv/bin/activate");
+    let pyproject_path = base_path.join("pyproject.toml");
+    if pyproject_path.exists() {
+        configure_pyproject(&pyproject_path, project_name, &source_name, options)?;
+        if let Some(ci) = options.ci {
+            // --branch only applies to a repository created here
+            let branch = options
+                .branch
+                .clone()
+                .filter(|_| !git::is_inside_work_tree(base_path))
+                .unwrap_or_else(|| git::default_branch(base_path));
+            let path = ci.write(base_path, &branch, false)?;
             println!(
-                "\nThis will activate the virtual environment for project '{}'.",
-                project_name
+                "Added a {} CI workflow in {}.",
+                ci,
+                path.strip_prefix(base_path).unwrap_or(&path).display()
             );
-        } else {
-            eprintln!(
-                "Error: Virtual environment for project '{}' not found.",
-                project_name
+        }
+    }
+
+    if options.pre_commit {
+        hooks::write_pre_commit_config(base_path)?;
+    }
+
+    match &workspace_root {
+        Some(root) => {
+            workspace::register_member(root, base_path)?;
+            println!(
+                "Added '{}' to the workspace at {}.",
+                project_name,
+                root.display()
             );
+            println!("Run `uv sync` in the workspace root to install it.");
+            if git::is_inside_work_tree(base_path) {
+                init_git(base_path, options)?;
+            } else {
+                if options.pre_commit {
+                    println!("Warning: the workspace is not a Git repository; no pre-commit hook was installed. Run `hajime hooks install` after `git init`.");
+                }
+                warn_skipped_git_options(options, "the workspace is not a Git repository");
+            }
         }
+        None => {
+            init_git(base_path, options)?;
+            setup_venv(base_path, project_name);
+        }
+    }
 
-        // Install the current packages in the virtual environment
-        let uv_pip_install = Command::new("uv")
-            .args(&["pip", "install", "-e", ".[dev]"])
-            .current_dir(base_path)
-            .output()
-            .expect("Failed to install the project in the virtual environment.");
+    println!("\nProject '{project_name}' created successfully!");
+    Ok(())
+}
 
-        if !uv_pip_install.status.success() {
-            eprintln!("Error: Failed to install the project in the virtual environment.");
+/// Builds the placeholder values for rendering a project template.
+/// The author defaults come from the git configuration seen from `dir`.
+pub fn template_context(
+    dir: &Path,
+    project_name: &str,
+    module_name: &str,
+    options: &NewOptions,
+) -> TemplateContext {
+    let author = options.author_name(dir);
+    let author_email = options.author_email(dir);
+
+    let mut context = TemplateContext::default();
+    context.set("project_name", project_name);
+    context.set("normalized_name", &naming::normalize(project_name));
+    context.set("module_name", module_name);
+    context.set("package_dir", &options.package_dir(module_name));
+    context.set("description", &options.description(project_name));
+    context.set("author", &author);
+    context.set("author_email", &author_email);
+    context.set("authors", &authors_array(&author, &author_email));
+    context.set("license", options.license.as_deref().unwrap_or_default());
+    context.set(
+        "python_version",
+        options
+            .python_version
+            .as_deref()
+            .unwrap_or(DEFAULT_PYTHON_VERSION),
+    );
+    context
+}
+
+/// Applies the options to a freshly rendered `pyproject.toml`: points the build
+/// configuration at the package directory and fills in the project metadata.
+/// With no explicit backend, the backend already in the file (or hatchling) is kept.
+pub fn configure_pyproject(
+    path: &Path,
+    project_name: &str,
+    module_name: &str,
+    options: &NewOptions,
+) -> io::Result<()> {
+    let mut doc = pyproject::read(path)?;
+    let dir = path.parent().unwrap_or(Path::new("."));
+
+    // The Rust template configures maturin itself
+    if !options.is_rust() {
+        let chosen = match options.backend.or_else(|| Backend::detect(&doc)) {
+            Some(detected) if options.backend.is_none() => detected,
+            chosen => {
+                let chosen = chosen.unwrap_or_default();
+                chosen.set_build_system(&mut doc);
+                chosen
+            }
+        };
+        chosen.configure_packages(&mut doc, options.layout, module_name);
+    }
+    tools::configure(&mut doc, options, module_name);
+    if options.is_rust() {
+        // maturin builds take the version from Cargo.toml; keep both in step
+        let version = pyproject::table_mut(&mut doc, &["tool", "hajime", "version"]);
+        version["files"] = value(pyproject::string_array(&["Cargo.toml".to_string()]));
+    }
+
+    let project = pyproject::table_mut(&mut doc, &["project"]);
+    if options.description.is_some() {
+        project["description"] = value(options.description(project_name));
+    }
+    if let Some(license) = &options.license {
+        let with_file = dir.join(LICENSE_FILE).exists();
+        license::apply(&mut doc, license, with_file)?;
+    }
+
+    if let Some(cli) = options.cli {
+        cli.configure(&mut doc, project_name, module_name);
+    } else if options.entry_point {
+        let package_dir = dir.join(options.package_dir(module_name));
+        if package_dir.join("greet.py").exists() {
+            let scripts = pyproject::table_mut(&mut doc, &["project", "scripts"]);
+            scripts[project_name] = value(format!("{}.greet:hello", module_name));
         } else {
-            println!("Project installed successfully in the virtual environment.\n");
+            println!("Warning: the template has no greet.py; no console script was registered.");
         }
-    } else {
+    }
+
+    pyproject::write(path, &doc)
+}
+
+/// Writes the text of license `id` to `LICENSE` in `dir`, unless the template
+/// already provided one. Unknown identifiers only get a warning.
+fn write_license(dir: &Path, id: &str, options: &NewOptions) -> io::Result<()> {
+    if dir.join(LICENSE_FILE).exists() {
+        return Ok(());
+    }
+    match License::find(id) {
+        Some(license) => license.write_file(dir, &options.author_name(dir)),
+        None => {
+            println!(
+                "Warning: no license text is bundled for '{}'; add a LICENSE file yourself. Known licenses: {}.",
+                id,
+                license::known_ids()
+            );
+            Ok(())
+        }
+    }
+}
+
+/// Prints `paths` as a directory tree below `root`.
+fn print_tree(root: &str, paths: &[PathBuf]) {
+    #[derive(Default)]
+    struct Node(BTreeMap<String, Node>);
+
+    fn print_children(node: &Node, prefix: &str) {
+        let count = node.0.len();
+        for (index, (name, child)) in node.0.iter().enumerate() {
+            let last = index + 1 == count;
+            let suffix = if child.0.is_empty() { "" } else { "/" };
+            println!(
+                "{}{}{}{}",
+                prefix,
+                if last { "└── " } else { "├── " },
+                name,
+                suffix
+            );
+            print_children(
+                child,
+                &format!("{}{}", prefix, if last { "    " } else { "│   " }),
+            );
+        }
+    }
+
+    let mut tree = Node::default();
+    for path in paths {
+        let mut node = &mut tree;
+        for component in path.components() {
+            let name = component.as_os_str().to_string_lossy().into_owned();
+            node = node.0.entry(name).or_default();
+        }
+    }
+
+    println!("{}/", root);
+    print_children(&tree, "");
+}
+
+/// Initializes a git repository in `dir`, if git is installed and `dir` is not
+/// already inside a work tree. Then sets the default branch and remote and makes
+/// the initial commit, as requested in `options`.
+pub fn init_git(dir: &Path, options: &NewOptions) -> io::Result<()> {
+    if !is_git_installed() {
+        println!("Warning: Git is not installed. Skipping Git repository initialization.");
+        warn_skipped_git_options(options, "Git is not installed");
+        return Ok(());
+    }
+    if git::is_inside_work_tree(dir) {
+        println!("Skipping Git initialization: already inside a Git work tree.");
+        warn_skipped_git_options(options, "the project is inside an existing Git work tree");
+        if options.pre_commit {
+            // The enclosing repository has a single hook shared by all of its projects
+            println!("Note: no pre-commit hook was installed in the enclosing repository. Run `hajime hooks install` in the project that should own it.");
+        }
+        return Ok(());
+    }
+
+    git::init(dir, options.branch.as_deref())?;
+    println!("Initialized Git repository.");
+
+    if options.pre_commit {
+        hooks::install_hook(dir, false)?;
+        println!("Installed a Git pre-commit hook running ruff.");
+    }
+    if let Some(url) = &options.remote {
+        git::add_remote(dir, "origin", url)?;
+        println!("Added remote 'origin' ({}).", url);
+    }
+    if options.initial_commit {
+        git::commit_all(dir, INITIAL_COMMIT_MESSAGE)?;
+        println!(
+            "Committed the project skeleton: \"{}\"",
+            INITIAL_COMMIT_MESSAGE
+        );
+    }
+    Ok(())
+}
+
+/// Warns about `--branch`, `--remote` and `--initial-commit`, which only apply to a
+/// repository `hajime` creates itself.
+fn warn_skipped_git_options(options: &NewOptions, reason: &str) {
+    let mut skipped = Vec::new();
+    if options.branch.is_some() {
+        skipped.push("--branch");
+    }
+    if options.remote.is_some() {
+        skipped.push("--remote");
+    }
+    if options.initial_commit {
+        skipped.push("--initial-commit");
+    }
+    if !skipped.is_empty() {
+        println!(
+            "Warning: {} ignored because {}.",
+            skipped.join(", "),
+            reason
+        );
+    }
+}
+
+/// Creates `.venv` in `dir` with `uv` and installs the project into it.
+pub fn setup_venv(dir: &Path, project_name: &str) {
+    if !is_uv_installed() {
         println!("`uv` is not installed! Virtual environment is not created.\n");
         println!("Follow the instructions at https://docs.astral.sh/uv/#getting-started to install `uv`.");
+        return;
     }
 
-    println!("\nProject '{project_name}' created successfully!");
-    Ok(())
+    println!("Detected `uv`. Creating virtual environment...");
+    let uv_command = Command::new("uv")
+        .args(["venv", ".venv"])
+        .current_dir(dir)
+        .output()
+        .expect("Failed to create virtual environment using `uv`.");
+
+    if !uv_command.status.success() {
+        eprintln!("Error: Failed to create virtual environment using `uv`.");
+    } else {
+        println!("Virtual environment `.venv` created successfully.\n");
+    }
+
+    // Check if the virtual environment exists
+    if dir.join(".venv").exists() {
+        println!("To activate the virtual environment, run:");
+        println!("    source .venv/bin/activate");
+        println!(
+            "\nThis will activate the virtual environment for project '{}'.",
+            project_name
+        );
+    } else {
+        eprintln!(
+            "Error: Virtual environment for project '{}' not found.",
+            project_name
+        );
+    }
+
+    // Install the current packages in the virtual environment
+    let uv_pip_install = Command::new("uv")
+        .args(["pip", "install", "-e", ".[dev]"])
+        .current_dir(dir)
+        .output()
+        .expect("Failed to install the project in the virtual environment.");
+
+    if !uv_pip_install.status.success() {
+        eprintln!("Error: Failed to install the project in the virtual environment.");
+    } else {
+        println!("Project installed successfully in the virtual environment.\n");
+    }
 }
@@ -1,7 +1,7 @@
 use crate::helpers::{get_latest_wheel_file, is_rust_python_project};
 use keyring::Entry;
-use rpassword;
 use std::io::{self, Write};
+use std::path::Path;
 use std::process::{Command, Stdio};
 
 const SERVICE_NAME: &str = "hajime-cli";
@@ -25,13 +25,13 @@ impl PyPiConfig {
     fn get_token(&self) -> io::Result<String> {
         self.keyring_entry()
             .get_password()
-            .map_err(|e| io::Error::new(io::ErrorKind::Other, e))
+            .map_err(io::Error::other)
     }
 
     fn save_token(&self, token: &str) -> io::Result<()> {
         self.keyring_entry()
             .set_password(token)
-            .map_err(|e| io::Error::new(io::ErrorKind::Other, e))
+            .map_err(io::Error::other)
     }
 
     fn prompt_token(&self) -> io::Result<String> {
@@ -40,12 +40,8 @@ impl PyPiConfig {
         io::stdout().flush()?; // Ensure the prompt is printed before input
 
         // Use rpassword to read the password securely
-        let token = rpassword::read_password().map_err(|e| {
-            io::Error::new(
-                io::ErrorKind::Other,
-                format!("Error reading password: {}", e),
-            )
-        })?;
+        let token = rpassword::read_password()
+            .map_err(|e| io::Error::other(format!("Error reading password: {}", e)))?;
 
         Ok(token.trim().to_string())
     }
@@ -64,7 +60,9 @@ impl PyPiConfig {
     }
 }
 
+/// Uploads the latest wheel of the project in `root` to PyPI.
 pub fn publish_package(
+    root: &Path,
     account: Option<String>,
     override_token: bool,
     use_maturin: bool,
@@ -91,15 +89,15 @@ pub fn publish_package(
     // let is_rust_python_project =
     //     Path::new("Cargo.toml").exists() && Path::new("target/wheels").exists();
 
-    if use_maturin || is_rust_python_project() {
+    if use_maturin || is_rust_python_project(root) {
         // Find the latest `.whl` file in `target/wheels`
 
-        if let Some(latest_wheel) = get_latest_wheel_file("target/wheels") {
+        if let Some(latest_wheel) = get_latest_wheel_file(&root.join("target/wheels")) {
             println!("Using maturin to upload the package: {:?}", latest_wheel);
 
             let command = Command::new("maturin")
-                .args(&["upload", "-u", "__token__", "-p", &token])
-                .arg(latest_wheel)
+                .args(["upload", "-u", "__token__", "-p", &token])
+                .arg(&latest_wheel)
                 .stdout(Stdio::inherit())
                 .stderr(Stdio::inherit())
                 .spawn();
@@ -113,17 +111,13 @@ pub fn publish_package(
                             config.account
                         );
                     } else {
-                        return Err(io::Error::new(
-                            io::ErrorKind::Other,
+                        return Err(io::Error::other(
                             "maturin upload failed. Check the output above for details.",
                         ));
                     }
                 }
                 Err(e) => {
-                    return Err(io::Error::new(
-                        io::ErrorKind::Other,
-                        format!("Error running maturin: {}", e),
-                    ));
+                    return Err(io::Error::other(format!("Error running maturin: {}", e)));
                 }
             }
         } else {
@@ -134,7 +128,7 @@ pub fn publish_package(
         }
     } else {
         // Check if dist directory exists
-        if !std::path::Path::new("dist").exists() {
+        if !root.join("dist").exists() {
             return Err(io::Error::new(
                 io::ErrorKind::NotFound,
                 "No dist directory found. Please run 'hajime build' first.",
@@ -142,7 +136,7 @@ pub fn publish_package(
         }
 
         // Determine the latest wheel file
-        let latest_wheel = get_latest_wheel_file("dist");
+        let latest_wheel = get_latest_wheel_file(&root.join("dist"));
         if latest_wheel.is_none() {
             return Err(io::Error::new(
                 io::ErrorKind::NotFound,
@@ -155,12 +149,14 @@ pub fn publish_package(
 
         // Run twine to publish the package and stream output
         let command = Command::new("uv")
-            .args(&["run", "twine", "upload"])
-            .arg(latest_wheel)
+            .args(["run", "twine", "upload"])
+            // Relative to `root`, where the command runs
+            .arg(latest_wheel.strip_prefix(root).unwrap_or(&latest_wheel))
             .arg("--username")
             .arg("__token__") // PyPI uses `__token__` as the username for API tokens
             .arg("--password")
             .arg(&token) // Pass the actual token as the password
+            .current_dir(root)
             .stdout(Stdio::inherit()) // Stream stdout to hajime's stdout
             .stderr(Stdio::inherit()) // Stream stderr to hajime's stderr
             .spawn();
@@ -174,17 +170,13 @@ pub fn publish_package(
                         config.account
                     );
                 } else {
-                    return Err(io::Error::new(
-                        io::ErrorKind::Other,
+                    return Err(io::Error::other(
                         "twine upload failed. Check the output above for details.",
                     ));
                 }
             }
             Err(e) => {
-                return Err(io::Error::new(
-                    io::ErrorKind::Other,
-                    format!("Error running twine: {}", e),
-                ));
+                return Err(io::Error::other(format!("Error running twine: {}", e)));
             }
         }
     }
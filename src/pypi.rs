@@ -0,0 +1,135 @@
+use std::env;
+use std::process::Command;
+
+/// Base URL of the PyPI JSON API.
+pub const DEFAULT_INDEX_URL: &str = "https://pypi.org/pypi";
+
+/// Environment variable that overrides the index URL.
+pub const INDEX_URL_ENV: &str = "HAJIME_INDEX_URL";
+
+/// Result of looking up a project on the index.
+pub enum Lookup {
+    /// The project exists; holds its JSON metadata.
+    Found(serde_json::Value),
+    /// The index answered that there is no such project.
+    NotFound,
+    /// The index could not be reached, e.g. when offline.
+    Unavailable(String),
+}
+
+/// The JSON API base URL: `url` if given, else `$HAJIME_INDEX_URL`, else the
+/// `configured` one, else PyPI.
+pub fn index_url(url: Option<&str>, configured: Option<&str>) -> String {
+    url.map(str::to_string)
+        .or_else(|| env::var(INDEX_URL_ENV).ok().filter(|u| !u.is_empty()))
+        .or_else(|| configured.map(str::to_string))
+        .unwrap_or_else(|| DEFAULT_INDEX_URL.to_string())
+        .trim_end_matches('/')
+        .to_string()
+}
+
+/// Looks up `name` at `<index_url>/<name>/json`.
+pub fn lookup(index_url: &str, name: &str) -> Lookup {
+    let url = format!("{}/{}/json", index_url, name);
+    let output = match Command::new("curl")
+        .args(["-sS", "-L", "--max-time", "10", "-w", "\n%{http_code}"])
+        .arg(&url)
+        .output()
+    {
+        Ok(output) => output,
+        Err(e) => return Lookup::Unavailable(format!("could not run curl: {}", e)),
+    };
+    if !output.status.success() {
+        return Lookup::Unavailable(String::from_utf8_lossy(&output.stderr).trim().to_string());
+    }
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let (body, status) = stdout.rsplit_once('\n').unwrap_or(("", &stdout));
+    match status.trim() {
+        "200" => match serde_json::from_str(body) {
+            Ok(json) => Lookup::Found(json),
+            Err(e) => Lookup::Unavailable(format!("invalid JSON from {}: {}", url, e)),
+        },
+        "404" => Lookup::NotFound,
+        status => Lookup::Unavailable(format!("HTTP {} from {}", status, url)),
+    }
+}
+
+/// Fetches the latest version of the package from the index, if online.
+/// Returns `None` if the user is offline or if the package is not found.
+pub fn latest_version(index_url: &str, name: &str) -> Option<String> {
+    match lookup(index_url, name) {
+        Lookup::Found(json) => json["info"]["version"].as_str().map(|s| s.to_string()),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use std::io::{BufRead, BufReader, Write};
+    use std::net::TcpListener;
+    use std::thread;
+
+    /// Serves a JSON API on localhost where only `projects` exist, each at
+    /// version 1.0.0, and returns its base URL.
+    pub fn stand_in_index(projects: &[&str]) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/pypi", listener.local_addr().unwrap());
+        let projects: Vec<String> = projects.iter().map(|p| p.to_string()).collect();
+        thread::spawn(move || {
+            for mut stream in listener.incoming().flatten() {
+                let mut request_line = String::new();
+                BufReader::new(&stream)
+                    .read_line(&mut request_line)
+                    .unwrap();
+                let path = request_line.split_whitespace().nth(1).unwrap_or("");
+                let found = projects.iter().any(|p| path == format!("/pypi/{}/json", p));
+                let (status, body) = if found {
+                    ("200 OK", r#"{"info": {"version": "1.0.0"}}"#)
+                } else {
+                    ("404 Not Found", r#"{"message": "Not Found"}"#)
+                };
+                let _ = write!(
+                    stream,
+                    "HTTP/1.1 {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    status,
+                    body.len(),
+                    body
+                );
+            }
+        });
+        url
+    }
+
+    #[test]
+    fn lookup_found_and_not_found() {
+        let index = stand_in_index(&["demo"]);
+        match lookup(&index, "demo") {
+            Lookup::Found(json) => assert_eq!(json["info"]["version"], "1.0.0"),
+            _ => panic!("expected demo to be found"),
+        }
+        assert!(matches!(lookup(&index, "other"), Lookup::NotFound));
+        assert_eq!(latest_version(&index, "demo").as_deref(), Some("1.0.0"));
+        assert_eq!(latest_version(&index, "other"), None);
+    }
+
+    #[test]
+    fn lookup_unreachable_index() {
+        assert!(matches!(
+            lookup("http://127.0.0.1:1", "demo"),
+            Lookup::Unavailable(_)
+        ));
+    }
+
+    #[test]
+    fn index_url_prefers_the_flag() {
+        assert_eq!(
+            index_url(
+                Some("http://localhost:8080/pypi/"),
+                Some("http://configured")
+            ),
+            "http://localhost:8080/pypi"
+        );
+    }
+}
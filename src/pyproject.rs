@@ -0,0 +1,70 @@
+use std::fs;
+use std::io;
+use std::path::Path;
+use toml_edit::{Array, DocumentMut, Item, Table, TableLike};
+
+/// Reads and parses a TOML file such as `pyproject.toml`.
+pub fn read(path: &Path) -> io::Result<DocumentMut> {
+    let content = fs::read_to_string(path)?;
+    content.parse::<DocumentMut>().map_err(|e| {
+        io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("Error parsing {}: {}", path.display(), e),
+        )
+    })
+}
+
+/// Writes a TOML document back to disk, preserving its formatting.
+pub fn write(path: &Path, doc: &DocumentMut) -> io::Result<()> {
+    fs::write(path, doc.to_string())
+}
+
+/// Returns the table at `keys`, creating any missing tables along the way.
+/// Inline tables on the path are expanded into regular tables.
+pub fn table_mut<'a>(doc: &'a mut DocumentMut, keys: &[&str]) -> &'a mut Table {
+    let mut table = doc.as_table_mut();
+    for key in keys {
+        let item = table.entry(key).or_insert_with(implicit_table);
+        if let Some(inline) = item.as_inline_table() {
+            let mut expanded = inline.clone().into_table();
+            expanded.set_implicit(true);
+            *item = Item::Table(expanded);
+        }
+        if !item.is_table() {
+            *item = implicit_table();
+        }
+        table = item.as_table_mut().expect("item was just made a table");
+    }
+    table
+}
+
+/// Removes the item at `keys`, if present.
+pub fn remove(doc: &mut DocumentMut, keys: &[&str]) {
+    let Some((last, parents)) = keys.split_last() else {
+        return;
+    };
+    let mut table: &mut dyn TableLike = doc.as_table_mut();
+    for key in parents {
+        match table.get_mut(key).and_then(Item::as_table_like_mut) {
+            Some(inner) => table = inner,
+            None => return,
+        }
+    }
+    table.remove(last);
+}
+
+/// Returns the value of `build-system.build-backend`, if set.
+pub fn build_backend(doc: &DocumentMut) -> Option<&str> {
+    doc.get("build-system")?.get("build-backend")?.as_str()
+}
+
+/// Builds a TOML array of strings.
+pub fn string_array<S: AsRef<str>>(items: &[S]) -> Array {
+    items.iter().map(|s| s.as_ref()).collect()
+}
+
+fn implicit_table() -> Item {
+    let mut table = Table::new();
+    table.set_implicit(true);
+    Item::Table(table)
+}
@@ -0,0 +1,368 @@
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// Name of the template used when `--template` is not given.
+pub const DEFAULT_TEMPLATE: &str = "default";
+
+/// Name of the template used for mixed Rust/Python projects (`--rust`).
+pub const RUST_TEMPLATE: &str = "pyo3";
+
+/// Built-in templates embedded in the binary, as `(name, files)` pairs.
+/// File paths are relative to the project root and may contain placeholders.
+const BUILTIN_TEMPLATES: &[(&str, &[(&str, &str)])] = &[
+    (
+        "default",
+        &[
+            (
+                "{{package_dir}}/__init__.py",
+                include_str!("../templates/default/module/__init__.py"),
+            ),
+            (
+                "{{package_dir}}/greet.py",
+                include_str!("../templates/default/module/greet.py"),
+            ),
+            (
+                "tests/__init__.py",
+                include_str!("../templates/default/tests/__init__.py"),
+            ),
+            (
+                "tests/test_greet.py",
+                include_str!("../templates/default/tests/test_greet.py"),
+            ),
+            (
+                "pyproject.toml",
+                include_str!("../templates/default/pyproject.toml"),
+            ),
+            ("README.md", include_str!("../templates/default/README.md")),
+        ],
+    ),
+    (
+        "pyo3",
+        &[
+            ("Cargo.toml", include_str!("../templates/pyo3/Cargo.toml")),
+            ("src/lib.rs", include_str!("../templates/pyo3/src/lib.rs")),
+            (
+                "python/{{module_name}}/__init__.py",
+                include_str!("../templates/pyo3/module/__init__.py"),
+            ),
+            (
+                "python/{{module_name}}/_core.pyi",
+                include_str!("../templates/pyo3/module/_core.pyi"),
+            ),
+            (
+                "python/{{module_name}}/py.typed",
+                include_str!("../templates/pyo3/module/py.typed"),
+            ),
+            (
+                "tests/__init__.py",
+                include_str!("../templates/pyo3/tests/__init__.py"),
+            ),
+            (
+                "tests/test_core.py",
+                include_str!("../templates/pyo3/tests/test_core.py"),
+            ),
+            (
+                "pyproject.toml",
+                include_str!("../templates/pyo3/pyproject.toml"),
+            ),
+            ("README.md", include_str!("../templates/pyo3/README.md")),
+        ],
+    ),
+];
+
+/// Values substituted for `{{name}}` placeholders when rendering a template.
+#[derive(Default)]
+pub struct TemplateContext {
+    vars: Vec<(String, String)>,
+}
+
+impl TemplateContext {
+    /// Sets a placeholder value, replacing any previous value for `key`.
+    pub fn set(&mut self, key: &str, value: &str) {
+        match self.vars.iter_mut().find(|(k, _)| k == key) {
+            Some((_, v)) => *v = value.to_string(),
+            None => self.vars.push((key.to_string(), value.to_string())),
+        }
+    }
+
+    /// Replaces every known `{{key}}` (or `{{ key }}`) in `input`.
+    /// Unknown placeholders are left untouched.
+    pub fn render(&self, input: &str) -> String {
+        let mut output = String::with_capacity(input.len());
+        let mut rest = input;
+
+        while let Some(start) = rest.find("{{") {
+            output.push_str(&rest[..start]);
+            let after = &rest[start + 2..];
+            let Some(end) = after.find("}}") else {
+                rest = &rest[start..];
+                break;
+            };
+
+            let key = after[..end].trim();
+            match self.vars.iter().find(|(k, _)| k == key) {
+                Some((_, value)) => output.push_str(value),
+                None => output.push_str(&rest[start..start + 2 + end + 2]),
+            }
+            rest = &after[end + 2..];
+        }
+
+        output.push_str(rest);
+        output
+    }
+}
+
+/// A single file of a template.
+pub struct TemplateFile {
+    /// Path relative to the project root, possibly containing placeholders.
+    pub path: String,
+    pub contents: Vec<u8>,
+}
+
+/// A project skeleton: a set of files with placeholders.
+pub struct Template {
+    pub name: String,
+    pub files: Vec<TemplateFile>,
+}
+
+impl Template {
+    /// Returns the built-in template with the given name, if any.
+    pub fn builtin(name: &str) -> Option<Template> {
+        let (name, files) = BUILTIN_TEMPLATES.iter().find(|(n, _)| *n == name)?;
+        Some(Template {
+            name: name.to_string(),
+            files: files
+                .iter()
+                .map(|(path, contents)| TemplateFile {
+                    path: path.to_string(),
+                    contents: contents.as_bytes().to_vec(),
+                })
+                .collect(),
+        })
+    }
+
+    /// Loads a user-supplied template from a directory.
+    /// Every file below `dir` becomes part of the template; `.git` is skipped.
+    pub fn from_dir(dir: &Path) -> io::Result<Template> {
+        let mut files = Vec::new();
+        collect_files(dir, dir, &mut files)?;
+        files.sort_by(|a, b| a.path.cmp(&b.path));
+
+        if files.is_empty() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("Template directory '{}' is empty.", dir.display()),
+            ));
+        }
+
+        Ok(Template {
+            name: dir.display().to_string(),
+            files,
+        })
+    }
+
+    /// Resolves `spec` as a built-in template name, falling back to a directory path.
+    pub fn resolve(spec: &str) -> io::Result<Template> {
+        if let Some(template) = Template::builtin(spec) {
+            return Ok(template);
+        }
+
+        let dir = Path::new(spec);
+        if dir.is_dir() {
+            return Template::from_dir(dir);
+        }
+
+        let builtin: Vec<&str> = BUILTIN_TEMPLATES.iter().map(|(n, _)| *n).collect();
+        Err(io::Error::new(
+            io::ErrorKind::NotFound,
+            format!(
+                "Template '{}' is neither a built-in template ({}) nor a directory.",
+                spec,
+                builtin.join(", ")
+            ),
+        ))
+    }
+
+    /// Returns the rendered paths of the template's files, relative to the project root.
+    pub fn paths(&self, context: &TemplateContext) -> Vec<PathBuf> {
+        self.files
+            .iter()
+            .map(|file| PathBuf::from(context.render(&file.path)))
+            .collect()
+    }
+
+    /// Renders every file of the template below `dest`.
+    /// Text files have their placeholders substituted; other files are copied as-is.
+    /// Returns the paths of the written files.
+    pub fn render(&self, context: &TemplateContext, dest: &Path) -> io::Result<Vec<PathBuf>> {
+        self.render_filtered(context, dest, |_| true)
+    }
+
+    /// Like [`Template::render`], but only writes files for which `keep` returns true.
+    /// `keep` receives the rendered path relative to `dest`.
+    pub fn render_filtered<F>(
+        &self,
+        context: &TemplateContext,
+        dest: &Path,
+        keep: F,
+    ) -> io::Result<Vec<PathBuf>>
+    where
+        F: Fn(&Path) -> bool,
+    {
+        let mut written = Vec::new();
+
+        for file in &self.files {
+            let relative = PathBuf::from(context.render(&file.path));
+            if !keep(&relative) {
+                continue;
+            }
+
+            let path = dest.join(relative);
+            if let Some(parent) = path.parent() {
+                fs::create_dir_all(parent)?;
+            }
+
+            match std::str::from_utf8(&file.contents) {
+                Ok(text) => fs::write(&path, context.render(text))?,
+                Err(_) => fs::write(&path, &file.contents)?,
+            }
+            written.push(path);
+        }
+
+        Ok(written)
+    }
+}
+
+fn collect_files(root: &Path, dir: &Path, files: &mut Vec<TemplateFile>) -> io::Result<()> {
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.file_name().is_some_and(|name| name == ".git") {
+            continue;
+        }
+
+        if path.is_dir() {
+            collect_files(root, &path, files)?;
+        } else {
+            let relative = path.strip_prefix(root).unwrap_or(&path);
+            let components: Vec<String> = relative
+                .components()
+                .map(|c| c.as_os_str().to_string_lossy().into_owned())
+                .collect();
+            files.push(TemplateFile {
+                path: components.join("/"),
+                contents: fs::read(&path)?,
+            });
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A fresh, empty directory below the system temporary directory.
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("hajime-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn context() -> TemplateContext {
+        let mut context = TemplateContext::default();
+        context.set("project_name", "demo");
+        context.set("module_name", "demo_pkg");
+        context
+    }
+
+    #[test]
+    fn render_substitutes_placeholders() {
+        let mut context = context();
+        assert_eq!(
+            context.render("name = \"{{project_name}}\"\nimport {{ module_name }}\n"),
+            "name = \"demo\"\nimport demo_pkg\n"
+        );
+        context.set("project_name", "other");
+        assert_eq!(
+            context.render("{{project_name}}{{project_name}}"),
+            "otherother"
+        );
+    }
+
+    #[test]
+    fn render_leaves_unknown_placeholders() {
+        let context = context();
+        assert_eq!(
+            context.render("${{ matrix.python-version }} {{unknown}} {{project_name}}"),
+            "${{ matrix.python-version }} {{unknown}} demo"
+        );
+        assert_eq!(context.render("{{project_name"), "{{project_name");
+    }
+
+    #[test]
+    fn resolve_builtin_and_directory_templates() {
+        let builtin = Template::resolve(DEFAULT_TEMPLATE).unwrap();
+        assert_eq!(builtin.name, "default");
+        assert!(builtin.files.iter().any(|f| f.path == "pyproject.toml"));
+
+        let dir = temp_dir("template-dir");
+        fs::create_dir_all(dir.join("{{module_name}}")).unwrap();
+        fs::create_dir_all(dir.join(".git")).unwrap();
+        fs::write(dir.join("{{module_name}}/__init__.py"), "").unwrap();
+        fs::write(dir.join("README.md"), "# {{project_name}}\n").unwrap();
+        fs::write(dir.join(".git/HEAD"), "ref: refs/heads/main\n").unwrap();
+
+        let template = Template::resolve(dir.to_str().unwrap()).unwrap();
+        let paths: Vec<&str> = template.files.iter().map(|f| f.path.as_str()).collect();
+        assert_eq!(paths, ["README.md", "{{module_name}}/__init__.py"]);
+        assert_eq!(template.name, dir.display().to_string());
+
+        let empty = temp_dir("template-empty");
+        assert!(Template::resolve(empty.to_str().unwrap()).is_err());
+        assert!(Template::resolve("no-such-template").is_err());
+        fs::remove_dir_all(dir).unwrap();
+        fs::remove_dir_all(empty).unwrap();
+    }
+
+    #[test]
+    fn render_filtered_writes_only_kept_files() {
+        let template = Template {
+            name: "test".to_string(),
+            files: vec![
+                TemplateFile {
+                    path: "{{module_name}}/__init__.py".to_string(),
+                    contents: b"NAME = \"{{project_name}}\"\n".to_vec(),
+                },
+                TemplateFile {
+                    path: "README.md".to_string(),
+                    contents: b"# {{project_name}}\n".to_vec(),
+                },
+                TemplateFile {
+                    path: "logo.bin".to_string(),
+                    contents: vec![0xff, 0xfe, b'{', b'{'],
+                },
+            ],
+        };
+        let dest = temp_dir("template-render");
+
+        let written = template
+            .render_filtered(&context(), &dest, |path| path != Path::new("README.md"))
+            .unwrap();
+        assert_eq!(
+            written,
+            [dest.join("demo_pkg/__init__.py"), dest.join("logo.bin")]
+        );
+        assert!(!dest.join("README.md").exists());
+        assert_eq!(
+            fs::read_to_string(dest.join("demo_pkg/__init__.py")).unwrap(),
+            "NAME = \"demo\"\n"
+        );
+        assert_eq!(
+            fs::read(dest.join("logo.bin")).unwrap(),
+            [0xff, 0xfe, b'{', b'{']
+        );
+        fs::remove_dir_all(dest).unwrap();
+    }
+}
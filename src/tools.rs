@@ -0,0 +1,99 @@
+use crate::layout::Layout;
+use crate::new::{NewOptions, DEFAULT_PYTHON_VERSION};
+use crate::pyproject::{self, string_array};
+use toml_edit::{value, Array, DocumentMut, Value};
+
+/// Ruff rules enabled in every new project: pycodestyle, Pyflakes, isort,
+/// flake8-bugbear and pyupgrade.
+const RUFF_RULES: &[&str] = &["E", "F", "W", "I", "B", "UP"];
+
+/// Rules added by the strict profile: naming, simplifications, comprehensions,
+/// pytest style and Ruff's own rules.
+const STRICT_RUFF_RULES: &[&str] = &["N", "SIM", "C4", "PT", "RUF"];
+
+/// Adds `[tool.ruff]`, `[tool.ruff.lint]`, `[tool.pytest.ini_options]` and, if
+/// enabled, `[tool.mypy]` to `pyproject.toml`. Sections the template already
+/// configures are left alone.
+pub fn configure(doc: &mut DocumentMut, options: &NewOptions, module_name: &str) {
+    let python_version = options
+        .python_version
+        .as_deref()
+        .unwrap_or(DEFAULT_PYTHON_VERSION);
+    let layout = if options.is_rust() {
+        Layout::Flat
+    } else {
+        options.layout
+    };
+
+    if !has_table(doc, &["tool", "ruff"]) {
+        let ruff = pyproject::table_mut(doc, &["tool", "ruff"]);
+        ruff["line-length"] = value(88);
+        ruff["target-version"] = value(format!("py{}", python_version.replace('.', "")));
+        if layout == Layout::Src {
+            ruff["src"] = value(string_array(&["src", "tests"]));
+        }
+
+        let mut rules = RUFF_RULES.to_vec();
+        if options.strict {
+            rules.extend(STRICT_RUFF_RULES);
+        }
+        let lint = pyproject::table_mut(doc, &["tool", "ruff", "lint"]);
+        lint["select"] = value(string_array(&rules));
+    }
+
+    if !has_table(doc, &["tool", "pytest", "ini_options"]) {
+        let mut addopts = vec!["-ra"];
+        if options.strict {
+            addopts.extend(["--strict-markers", "--strict-config"]);
+        }
+        addopts.extend(layout.pytest_addopts());
+
+        let pytest = pyproject::table_mut(doc, &["tool", "pytest", "ini_options"]);
+        pytest["testpaths"] = value(string_array(&["tests"]));
+        pytest["addopts"] = value(addopts.join(" "));
+        if options.strict {
+            pytest["xfail_strict"] = value(true);
+        }
+    }
+
+    if options.mypy_enabled() && !has_table(doc, &["tool", "mypy"]) {
+        let mypy = pyproject::table_mut(doc, &["tool", "mypy"]);
+        mypy["python_version"] = value(python_version);
+        mypy["files"] = value(string_array(&[options.package_dir(module_name)]));
+        if options.strict {
+            mypy["strict"] = value(true);
+        } else {
+            mypy["check_untyped_defs"] = value(true);
+            mypy["warn_unused_ignores"] = value(true);
+        }
+
+        add_dev_dependency(doc, "mypy");
+    }
+}
+
+fn has_table(doc: &DocumentMut, keys: &[&str]) -> bool {
+    let mut item = doc.as_item();
+    for key in keys {
+        match item.get(key) {
+            Some(inner) => item = inner,
+            None => return false,
+        }
+    }
+    item.is_table_like()
+}
+
+/// Appends `requirement` to the `dev` extra unless it is already listed.
+fn add_dev_dependency(doc: &mut DocumentMut, requirement: &str) {
+    let extras = pyproject::table_mut(doc, &["project", "optional-dependencies"]);
+    let dev = extras.entry("dev").or_insert_with(|| value(Array::new()));
+    if let Some(dev) = dev.as_array_mut() {
+        if !dev.iter().any(|d| d.as_str() == Some(requirement)) {
+            // Match the formatting of the existing entries, e.g. one per line
+            let mut item = Value::from(requirement);
+            if let Some(last) = dev.iter().last() {
+                *item.decor_mut() = last.decor().clone();
+            }
+            dev.push_formatted(item);
+        }
+    }
+}
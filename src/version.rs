@@ -0,0 +1,1125 @@
+use crate::changelog;
+use crate::git;
+use crate::helpers::requirement_name;
+use crate::naming;
+use crate::pyproject;
+use std::cmp::Ordering;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use toml_edit::value;
+
+/// Kinds of pre-release, in the order PEP 440 sorts them.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum PreKind {
+    Alpha,
+    Beta,
+    Rc,
+}
+
+impl fmt::Display for PreKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            PreKind::Alpha => "a",
+            PreKind::Beta => "b",
+            PreKind::Rc => "rc",
+        })
+    }
+}
+
+/// A PEP 440 version such as `1!2.0.1rc2.post1.dev3+local.7`.
+///
+/// Parsing accepts the spellings PEP 440 normalizes (`v1.0`, `1.0-alpha.1`,
+/// `1.0-1`, upper case, ...) and `Display` prints the normalized form.
+#[derive(Clone, Debug)]
+pub struct Version {
+    pub epoch: u64,
+    pub release: Vec<u64>,
+    pub pre: Option<(PreKind, u64)>,
+    pub post: Option<u64>,
+    pub dev: Option<u64>,
+    pub local: Option<String>,
+}
+
+/// Splits a leading run of ASCII digits off `s`.
+fn take_number(s: &str) -> Option<(u64, &str)> {
+    let end = s.find(|c: char| !c.is_ascii_digit()).unwrap_or(s.len());
+    let number = s[..end].parse().ok()?;
+    Some((number, &s[end..]))
+}
+
+/// Strips one optional `.`, `-` or `_` separator.
+fn skip_separator(s: &str) -> &str {
+    s.strip_prefix(['.', '-', '_']).unwrap_or(s)
+}
+
+/// Strips the first of `spellings` that `s` starts with, after an optional separator.
+fn take_label<'a>(s: &'a str, spellings: &[&str]) -> Option<&'a str> {
+    let rest = skip_separator(s);
+    spellings.iter().find_map(|label| rest.strip_prefix(label))
+}
+
+/// Splits an optional number (with an optional leading separator) off `s`.
+fn take_optional_number(s: &str) -> (u64, &str) {
+    take_number(skip_separator(s)).unwrap_or((0, s))
+}
+
+impl FromStr for Version {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || format!("'{}' is not a valid PEP 440 version", s);
+        let lower = s.trim().to_ascii_lowercase();
+        let (public, local) = match lower.split_once('+') {
+            Some((public, local)) => (public, Some(local)),
+            None => (lower.as_str(), None),
+        };
+        let mut rest = public.strip_prefix('v').unwrap_or(public);
+
+        let mut epoch = 0;
+        if let Some((number, after)) = take_number(rest) {
+            if let Some(after) = after.strip_prefix('!') {
+                epoch = number;
+                rest = after;
+            }
+        }
+
+        let mut release = Vec::new();
+        loop {
+            let (number, after) = take_number(rest).ok_or_else(invalid)?;
+            release.push(number);
+            rest = after;
+            match rest.strip_prefix('.') {
+                Some(after) if after.starts_with(|c: char| c.is_ascii_digit()) => rest = after,
+                _ => break,
+            }
+        }
+
+        let mut pre = None;
+        let pre_labels: [(PreKind, &[&str]); 3] = [
+            (PreKind::Alpha, &["alpha", "a"]),
+            (PreKind::Beta, &["beta", "b"]),
+            (PreKind::Rc, &["rc", "c", "preview", "pre"]),
+        ];
+        for (kind, spellings) in pre_labels {
+            if let Some(after) = take_label(rest, spellings) {
+                let (number, after) = take_optional_number(after);
+                pre = Some((kind, number));
+                rest = after;
+                break;
+            }
+        }
+
+        let mut post = None;
+        if let Some(after) = take_label(rest, &["post", "rev", "r"]) {
+            let (number, after) = take_optional_number(after);
+            post = Some(number);
+            rest = after;
+        } else if let Some(after) = rest.strip_prefix('-') {
+            // Implicit post release: `1.0-1`
+            let (number, after) = take_number(after).ok_or_else(invalid)?;
+            post = Some(number);
+            rest = after;
+        }
+
+        let mut dev = None;
+        if let Some(after) = take_label(rest, &["dev"]) {
+            let (number, after) = take_optional_number(after);
+            dev = Some(number);
+            rest = after;
+        }
+
+        if !rest.is_empty() {
+            return Err(invalid());
+        }
+
+        let local = match local {
+            Some(local) => {
+                let segments: Vec<&str> = local.split(['.', '-', '_']).collect();
+                if segments
+                    .iter()
+                    .any(|s| s.is_empty() || !s.chars().all(|c| c.is_ascii_alphanumeric()))
+                {
+                    return Err(invalid());
+                }
+                Some(segments.join("."))
+            }
+            None => None,
+        };
+
+        Ok(Version {
+            epoch,
+            release,
+            pre,
+            post,
+            dev,
+            local,
+        })
+    }
+}
+
+impl fmt::Display for Version {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.epoch != 0 {
+            write!(f, "{}!", self.epoch)?;
+        }
+        let release: Vec<String> = self.release.iter().map(u64::to_string).collect();
+        f.write_str(&release.join("."))?;
+        if let Some((kind, number)) = self.pre {
+            write!(f, "{}{}", kind, number)?;
+        }
+        if let Some(post) = self.post {
+            write!(f, ".post{}", post)?;
+        }
+        if let Some(dev) = self.dev {
+            write!(f, ".dev{}", dev)?;
+        }
+        if let Some(local) = &self.local {
+            write!(f, "+{}", local)?;
+        }
+        Ok(())
+    }
+}
+
+/// Compares local version labels: segment by segment, numbers after strings.
+fn compare_local(a: &str, b: &str) -> Ordering {
+    let key = |segment: &str| match segment.parse::<u64>() {
+        Ok(number) => (1, number, String::new()),
+        Err(_) => (0, 0, segment.to_string()),
+    };
+    a.split('.').map(key).cmp(b.split('.').map(key))
+}
+
+impl Ord for Version {
+    fn cmp(&self, other: &Self) -> Ordering {
+        let release = |v: &Version| {
+            let len = v.release.iter().rposition(|&n| n != 0).map_or(0, |i| i + 1);
+            v.release[..len].to_vec()
+        };
+        // A dev release without pre or post sorts before the pre-releases
+        let pre = |v: &Version| match v.pre {
+            Some((kind, number)) => (1, Some(kind), number),
+            None if v.post.is_none() && v.dev.is_some() => (0, None, 0),
+            None => (2, None, 0),
+        };
+        // Missing dev sorts after any dev release
+        let dev = |v: &Version| v.dev.map_or((1, 0), |n| (0, n));
+
+        self.epoch
+            .cmp(&other.epoch)
+            .then_with(|| release(self).cmp(&release(other)))
+            .then_with(|| pre(self).cmp(&pre(other)))
+            .then_with(|| self.post.cmp(&other.post))
+            .then_with(|| dev(self).cmp(&dev(other)))
+            .then_with(|| match (&self.local, &other.local) {
+                (Some(a), Some(b)) => compare_local(a, b),
+                (a, b) => a.is_some().cmp(&b.is_some()),
+            })
+    }
+}
+
+impl PartialOrd for Version {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl PartialEq for Version {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Version {}
+
+/// Which part of a version to bump.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BumpLevel {
+    Major,
+    Minor,
+    Patch,
+    /// Next number of the current pre-release.
+    Pre,
+    Alpha,
+    Beta,
+    Rc,
+    Post,
+    Dev,
+}
+
+impl FromStr for BumpLevel {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "major" | "macro" => Ok(BumpLevel::Major),
+            "minor" | "meso" => Ok(BumpLevel::Minor),
+            "patch" | "micro" => Ok(BumpLevel::Patch),
+            "pre" => Ok(BumpLevel::Pre),
+            "alpha" => Ok(BumpLevel::Alpha),
+            "beta" => Ok(BumpLevel::Beta),
+            "rc" => Ok(BumpLevel::Rc),
+            "post" => Ok(BumpLevel::Post),
+            "dev" => Ok(BumpLevel::Dev),
+            _ => Err(format!(
+                "Unknown bump level '{}' (expected major, minor, patch, pre, rc, beta, alpha, post or dev)",
+                s
+            )),
+        }
+    }
+}
+
+impl fmt::Display for BumpLevel {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            BumpLevel::Major => "major",
+            BumpLevel::Minor => "minor",
+            BumpLevel::Patch => "patch",
+            BumpLevel::Pre => "pre",
+            BumpLevel::Alpha => "alpha",
+            BumpLevel::Beta => "beta",
+            BumpLevel::Rc => "rc",
+            BumpLevel::Post => "post",
+            BumpLevel::Dev => "dev",
+        })
+    }
+}
+
+impl Version {
+    /// The same release without pre, post, dev and local parts.
+    fn final_release(&self) -> Version {
+        Version {
+            epoch: self.epoch,
+            release: self.release.clone(),
+            pre: None,
+            post: None,
+            dev: None,
+            local: None,
+        }
+    }
+
+    /// Increments release segment `index`, zeroing the segments after it.
+    /// A version leading up to exactly that release is finalized instead,
+    /// so `2.0.0rc1` and `2.0rc1.post1` bump to `2.0.0` and `2.0` rather than `3.0.0`.
+    fn bump_release(&self, index: usize) -> Version {
+        let mut next = self.final_release();
+        let lower_are_zero = self
+            .release
+            .get(index + 1..)
+            .is_none_or(|lower| lower.iter().all(|&n| n == 0));
+        if *self < next && lower_are_zero {
+            return next;
+        }
+        if next.release.len() < 3 {
+            next.release.resize(3, 0);
+        }
+        next.release[index] += 1;
+        for n in &mut next.release[index + 1..] {
+            *n = 0;
+        }
+        next
+    }
+
+    /// The same version without the dev and local parts, e.g. `1.0a1` for `1.0a1.dev2`.
+    fn without_dev(&self) -> Version {
+        Version {
+            dev: None,
+            local: None,
+            ..self.clone()
+        }
+    }
+
+    /// Starts or advances a pre-release of `kind`: `1.2.3` bumps to `1.2.4a0`,
+    /// `1.2.4a0` to `1.2.4a1` and, for a later kind, to `1.2.4b0`. A development
+    /// release of that pre-release is finalized: `1.2.4a1.dev2` bumps to `1.2.4a1`.
+    fn bump_pre(&self, kind: PreKind) -> Version {
+        if self.dev.is_some() && self.post.is_none() && self.pre.is_some_and(|(k, _)| k == kind) {
+            return self.without_dev();
+        }
+        let mut next = match self.pre {
+            Some(_) => self.final_release(),
+            None => self.bump_release(2),
+        };
+        next.pre = match self.pre {
+            Some((current, number)) if current == kind => Some((kind, number + 1)),
+            _ => Some((kind, 0)),
+        };
+        next
+    }
+
+    /// The version in the semver form Cargo requires, e.g. `1.2.0-rc.1`.
+    /// Errors for versions semver cannot express, such as post releases.
+    pub fn to_semver(&self) -> Result<String, String> {
+        if self.epoch != 0 || self.post.is_some() || self.dev.is_some() || self.release.len() > 3 {
+            return Err(format!(
+                "{} cannot be written to Cargo.toml; semver has no epochs, post or dev releases or more than three release numbers",
+                self
+            ));
+        }
+        let mut release = self.release.clone();
+        release.resize(3, 0);
+        let mut semver = format!("{}.{}.{}", release[0], release[1], release[2]);
+        if let Some((kind, number)) = self.pre {
+            let label = match kind {
+                PreKind::Alpha => "alpha",
+                PreKind::Beta => "beta",
+                PreKind::Rc => "rc",
+            };
+            semver.push_str(&format!("-{}.{}", label, number));
+        }
+        if let Some(local) = &self.local {
+            semver.push_str(&format!("+{}", local));
+        }
+        Ok(semver)
+    }
+
+    /// The next version at `level`. Errors if that would not be a higher version,
+    /// e.g. bumping `1.0rc1` to a beta.
+    pub fn bump(&self, level: BumpLevel) -> Result<Version, String> {
+        let next = match level {
+            BumpLevel::Major => self.bump_release(0),
+            BumpLevel::Minor => self.bump_release(1),
+            BumpLevel::Patch => self.bump_release(2),
+            BumpLevel::Pre => match self.pre {
+                Some((kind, _)) => self.bump_pre(kind),
+                None => {
+                    return Err(format!(
+                        "{} is not a pre-release; bump alpha, beta or rc to start one",
+                        self
+                    ))
+                }
+            },
+            BumpLevel::Alpha => self.bump_pre(PreKind::Alpha),
+            BumpLevel::Beta => self.bump_pre(PreKind::Beta),
+            BumpLevel::Rc => self.bump_pre(PreKind::Rc),
+            // A development release of a post release is finalized
+            BumpLevel::Post if self.post.is_some() && self.dev.is_some() => self.without_dev(),
+            BumpLevel::Post => {
+                let mut next = self.final_release();
+                next.pre = self.pre;
+                next.post = Some(self.post.map_or(0, |n| n + 1));
+                next
+            }
+            BumpLevel::Dev => {
+                let mut next = match (self.dev, self.pre, self.post) {
+                    (Some(_), _, _) => self.without_dev(),
+                    (None, Some(_), _) => self.bump(BumpLevel::Pre)?,
+                    (None, None, Some(_)) => self.bump(BumpLevel::Post)?,
+                    (None, None, None) => self.bump_release(2),
+                };
+                next.dev = Some(self.dev.map_or(0, |n| n + 1));
+                next
+            }
+        };
+
+        if next <= *self {
+            return Err(format!(
+                "Cannot bump {} to {}: {} is not a higher version",
+                self, level, next
+            ));
+        }
+        Ok(next)
+    }
+}
+
+/// The table declaring `name` and `version` in `path`: `[package]` in
+/// `Cargo.toml`, `[project]` otherwise.
+fn table_name(path: &Path) -> &'static str {
+    if path.ends_with("Cargo.toml") {
+        "package"
+    } else {
+        "project"
+    }
+}
+
+/// Reads the string `key` of the `[project]` or `[package]` table in `path`.
+fn read_field(path: &Path, key: &str) -> io::Result<String> {
+    pyproject::read(path)?
+        .get(table_name(path))
+        .and_then(|t| t.get(key))
+        .and_then(|v| v.as_str())
+        .map(str::to_string)
+        .ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::NotFound,
+                format!(
+                    "No {}.{} found in {}",
+                    table_name(path),
+                    key,
+                    path.display()
+                ),
+            )
+        })
+}
+
+/// Reads the project name declared in `path`.
+pub fn read_name(path: &Path) -> io::Result<String> {
+    read_field(path, "name")
+}
+
+/// Reads the project name and the version string declared in `path`, a
+/// `pyproject.toml` or `Cargo.toml`.
+pub fn read_raw(path: &Path) -> io::Result<(String, String)> {
+    Ok((read_field(path, "name")?, read_field(path, "version")?))
+}
+
+/// Reads the project name and version declared in `path`.
+pub fn read(path: &Path) -> io::Result<(String, Version)> {
+    let (name, version) = read_raw(path)?;
+    let version = version.parse().map_err(|e| {
+        io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("Invalid version in {}: {}", path.display(), e),
+        )
+    })?;
+    Ok((name, version))
+}
+
+/// Key paths of the versions declared in a TOML file: `[package]` and
+/// `[workspace.package]` in `Cargo.toml`, `[project]` otherwise.
+fn toml_version_keys(path: &Path) -> &'static [&'static [&'static str]] {
+    if path.ends_with("Cargo.toml") {
+        &[
+            &["package", "version"],
+            &["workspace", "package", "version"],
+        ]
+    } else {
+        &[&["project", "version"]]
+    }
+}
+
+/// Names that Python files such as `__init__.py`, `_version.py` or
+/// `docs/conf.py` assign the version to.
+const PYTHON_VERSION_NAMES: &[&str] = &["__version__", "VERSION", "version", "release"];
+
+/// Byte range of the quoted value in a top-level `__version__ = "..."`
+/// (optionally annotated with `: str`) assignment.
+fn python_version_value(line: &str) -> Option<Range<usize>> {
+    let name = PYTHON_VERSION_NAMES
+        .iter()
+        .find(|name| line.starts_with(**name))?;
+    let mut rest = line[name.len()..].trim_start();
+    if let Some(annotation) = rest.strip_prefix(':') {
+        rest = annotation.trim_start().strip_prefix("str")?.trim_start();
+    }
+    let rest = rest.strip_prefix('=')?.trim_start();
+    let quote = rest.chars().next().filter(|c| *c == '"' || *c == '\'')?;
+    let start = line.len() - rest.len() + 1;
+    let len = rest[1..].find(quote)?;
+    Some(start..start + len)
+}
+
+fn is_toml(path: &Path) -> bool {
+    path.extension().is_some_and(|e| e == "toml")
+}
+
+/// Every version string declared in `path`.
+fn read_versions(path: &Path) -> io::Result<Vec<String>> {
+    let versions: Vec<String> = if is_toml(path) {
+        let doc = pyproject::read(path)?;
+        toml_version_keys(path)
+            .iter()
+            .filter_map(|keys| {
+                keys.iter()
+                    .try_fold(doc.as_item(), |item, key| item.get(key))
+                    .and_then(|v| v.as_str())
+                    .map(str::to_string)
+            })
+            .collect()
+    } else {
+        fs::read_to_string(path)?
+            .lines()
+            .filter_map(|line| python_version_value(line).map(|range| line[range].to_string()))
+            .collect()
+    };
+    if versions.is_empty() {
+        return Err(io::Error::new(
+            io::ErrorKind::NotFound,
+            format!("No version found in {}", path.display()),
+        ));
+    }
+    Ok(versions)
+}
+
+/// The contents of `path` with every declared version replaced by `version`,
+/// in semver form for `Cargo.toml`.
+fn render(path: &Path, version: &Version) -> io::Result<String> {
+    read_versions(path)?;
+    if !is_toml(path) {
+        let content = fs::read_to_string(path)?;
+        let mut rendered = String::with_capacity(content.len());
+        for line in content.split_inclusive('\n') {
+            match python_version_value(line) {
+                Some(range) => {
+                    rendered.push_str(&line[..range.start]);
+                    rendered.push_str(&version.to_string());
+                    rendered.push_str(&line[range.end..]);
+                }
+                None => rendered.push_str(line),
+            }
+        }
+        return Ok(rendered);
+    }
+
+    let version = if path.ends_with("Cargo.toml") {
+        version
+            .to_semver()
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?
+    } else {
+        version.to_string()
+    };
+    let mut doc = pyproject::read(path)?;
+    for keys in toml_version_keys(path) {
+        let (key, tables) = keys.split_last().expect("key paths are not empty");
+        let declared = tables
+            .iter()
+            .try_fold(doc.as_item(), |item, key| item.get(key))
+            .and_then(|t| t.get(key))
+            .is_some_and(|v| v.is_str());
+        if declared {
+            pyproject::table_mut(&mut doc, tables)[*key] = value(&version);
+        }
+    }
+    Ok(doc.to_string())
+}
+
+/// The files the version of the project in `root` is written to: `primary`,
+/// `pyproject.toml` if it declares a version, and the files listed in
+/// `[tool.hajime.version] files`.
+pub fn version_files(root: &Path, primary: &Path) -> io::Result<Vec<PathBuf>> {
+    let mut files = vec![primary.to_path_buf()];
+    let pyproject_path = root.join("pyproject.toml");
+    if !pyproject_path.exists() {
+        return Ok(files);
+    }
+    let doc = pyproject::read(&pyproject_path)?;
+    let declared = doc.get("project").and_then(|p| p.get("version")).is_some();
+    if declared && !files.contains(&pyproject_path) {
+        files.push(pyproject_path);
+    }
+    let Some(configured) = doc
+        .get("tool")
+        .and_then(|t| t.get("hajime"))
+        .and_then(|h| h.get("version"))
+        .and_then(|v| v.get("files"))
+    else {
+        return Ok(files);
+    };
+    let configured = configured
+        .as_array()
+        .filter(|a| a.iter().all(|v| v.is_str()))
+        .ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                "[tool.hajime.version] files must be an array of paths.",
+            )
+        })?;
+    for file in configured.iter().filter_map(|v| v.as_str()) {
+        let path = root.join(file);
+        if !files.contains(&path) {
+            files.push(path);
+        }
+    }
+    Ok(files)
+}
+
+/// Writes `version` to every file in `files`. Nothing is written unless every
+/// file can take the new version.
+pub fn write_all(files: &[PathBuf], version: &Version) -> io::Result<()> {
+    let rendered = files
+        .iter()
+        .map(|path| {
+            render(path, version)
+                .map_err(|e| io::Error::new(e.kind(), format!("{}: {}", path.display(), e)))
+        })
+        .collect::<io::Result<Vec<String>>>()?;
+    for (path, content) in files.iter().zip(rendered) {
+        fs::write(path, content)?;
+    }
+    Ok(())
+}
+
+/// The file declaring the version of the project in `root`: `pyproject.toml`,
+/// or `Cargo.toml` when `pyproject.toml` takes its version from there.
+pub fn version_file(root: &Path) -> PathBuf {
+    let pyproject_path = root.join("pyproject.toml");
+    let cargo_path = root.join("Cargo.toml");
+    let declared = pyproject::read(&pyproject_path)
+        .is_ok_and(|doc| doc.get("project").and_then(|p| p.get("version")).is_some());
+    if !declared && cargo_path.exists() {
+        cargo_path
+    } else {
+        pyproject_path
+    }
+}
+
+/// Build requirements that derive the version from git tags.
+const VCS_VERSION_PLUGINS: &[&str] = &["hatch-vcs", "setuptools-scm"];
+
+/// Whether the project in `root` declares `dynamic = ["version"]` and takes the
+/// version from git tags (hatch-vcs, setuptools-scm or pdm's scm source).
+pub fn is_vcs_version(root: &Path) -> bool {
+    let Ok(doc) = pyproject::read(&root.join("pyproject.toml")) else {
+        return false;
+    };
+    let dynamic = doc
+        .get("project")
+        .and_then(|p| p.get("dynamic"))
+        .and_then(|d| d.as_array())
+        .is_some_and(|d| d.iter().any(|v| v.as_str() == Some("version")));
+    if !dynamic {
+        return false;
+    }
+
+    let tool = |keys: &[&str]| {
+        keys.iter()
+            .try_fold(doc.as_item(), |item, key| item.get(key))
+            .and_then(|v| v.as_str())
+            .map(str::to_string)
+    };
+    let plugin_required = doc
+        .get("build-system")
+        .and_then(|b| b.get("requires"))
+        .and_then(|r| r.as_array())
+        .is_some_and(|requires| {
+            requires.iter().filter_map(|r| r.as_str()).any(|r| {
+                let name = naming::normalize(&requirement_name(r));
+                VCS_VERSION_PLUGINS.contains(&name.as_str())
+            })
+        });
+    plugin_required
+        || tool(&["tool", "hatch", "version", "source"]).as_deref() == Some("vcs")
+        || tool(&["tool", "pdm", "version", "source"]).as_deref() == Some("scm")
+        || doc
+            .get("tool")
+            .and_then(|t| t.get("setuptools_scm"))
+            .is_some()
+}
+
+/// The version of a project that takes it from git tags.
+pub struct GitVersion {
+    /// Version of the latest `vX.Y.Z` tag.
+    pub tag: Version,
+    /// The version at `HEAD`: the tag's version on a clean checkout of the tag,
+    /// else the next version as a dev release, e.g. `1.2.4.dev3+gabc1234`.
+    pub current: Version,
+}
+
+/// Describes `HEAD` in the repository at `root` like setuptools-scm does.
+pub fn git_version(root: &Path) -> io::Result<GitVersion> {
+    let described = git::run(
+        root,
+        &[
+            "describe", "--tags", "--long", "--dirty", "--match", "v[0-9]*", "--match", "[0-9]*",
+        ],
+    )
+    .map_err(|_| {
+        io::Error::new(
+            io::ErrorKind::NotFound,
+            "No version tag (vX.Y.Z) found; create one with `hajime version set <version>`.",
+        )
+    })?;
+
+    let (described, dirty) = match described.strip_suffix("-dirty") {
+        Some(described) => (described, true),
+        None => (described.as_str(), false),
+    };
+    let invalid = || {
+        io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("Cannot parse `git describe` output '{}'", described),
+        )
+    };
+    let mut parts = described.rsplitn(3, '-');
+    let hash = parts.next().ok_or_else(invalid)?;
+    let distance: u64 = parts
+        .next()
+        .and_then(|d| d.parse().ok())
+        .ok_or_else(invalid)?;
+    let tag_name = parts.next().ok_or_else(invalid)?;
+    let tag: Version = tag_name.parse().map_err(|e| {
+        io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("Tag {}: {}", tag_name, e),
+        )
+    })?;
+    // `git describe` picks any of several tags on one commit; use the highest
+    let commit = format!("{}^{{commit}}", tag_name);
+    let tag = git::run(root, &["tag", "--points-at", &commit])?
+        .lines()
+        .filter_map(|name| name.parse::<Version>().ok())
+        .fold(tag, |highest, v| if v > highest { v } else { highest });
+
+    if distance == 0 && !dirty {
+        let current = tag.clone();
+        return Ok(GitVersion { tag, current });
+    }
+    let level = if tag.pre.is_some() {
+        BumpLevel::Pre
+    } else {
+        BumpLevel::Patch
+    };
+    let mut current = tag
+        .bump(level)
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+    current.dev = Some(distance);
+    current.local = Some(if dirty {
+        format!("{}.dirty", hash)
+    } else {
+        hash.to_string()
+    });
+    Ok(GitVersion { tag, current })
+}
+
+/// Creates the annotated tag `v<version>` at `HEAD` and returns its name.
+pub fn create_tag(root: &Path, version: &Version) -> io::Result<String> {
+    let name = format!("v{}", version);
+    if git::has_uncommitted_changes(root)? {
+        println!("Warning: uncommitted changes are not part of {}.", name);
+    }
+    git::run(
+        root,
+        &["tag", "-a", &name, "-m", &format!("Release {}", version)],
+    )?;
+    Ok(name)
+}
+
+/// What `hajime version` does with the version.
+pub enum Action {
+    Show,
+    /// Fail unless every version file declares the same version.
+    Check,
+    Bump(BumpLevel),
+    /// Bump by the level the Conventional Commits since the last release call for.
+    AutoBump,
+    Set(Version),
+}
+
+/// Prints, checks, bumps or sets the version of the project in `root`.
+///
+/// # Arguments
+/// * `root` - The project root.
+/// * `action` - Whether to print, check, bump or set the version.
+/// * `dry_run` - Print the change without writing it.
+/// * `json` - Print a JSON object instead of plain text.
+pub fn run(root: &Path, action: Action, dry_run: bool, json: bool) -> io::Result<()> {
+    if is_vcs_version(root) {
+        return run_git(root, action, dry_run, json);
+    }
+
+    let path = version_file(root);
+    let display = |path: &Path| {
+        path.strip_prefix(root)
+            .unwrap_or(path)
+            .display()
+            .to_string()
+    };
+    let file = display(&path);
+    let (name, current) = read(&path)?;
+    let files = version_files(root, &path)?;
+    let file_names: Vec<String> = files.iter().map(|f| display(f)).collect();
+
+    let new_version = match action {
+        Action::Show => {
+            if json {
+                let output = serde_json::json!({
+                    "name": name,
+                    "version": current.to_string(),
+                    "file": file,
+                });
+                println!("{}", output);
+            } else {
+                println!("{}", current);
+            }
+            return Ok(());
+        }
+        Action::Check => return check(&files, &file_names, &current, json),
+        Action::Bump(level) => current
+            .bump(level)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?,
+        Action::AutoBump => current
+            .bump(changelog::release_level(root, &current)?)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?,
+        Action::Set(version) => version,
+    };
+
+    if !dry_run {
+        write_all(&files, &new_version)?;
+    }
+    if json {
+        let output = serde_json::json!({
+            "name": name,
+            "previous": current.to_string(),
+            "version": new_version.to_string(),
+            "file": file,
+            "files": file_names,
+            "dry_run": dry_run,
+        });
+        println!("{}", output);
+    } else if dry_run {
+        println!(
+            "Would change the version from {} to {} in {}.",
+            current,
+            new_version,
+            file_names.join(", ")
+        );
+    } else {
+        println!(
+            "Changed the version from {} to {} in {}.",
+            current,
+            new_version,
+            file_names.join(", ")
+        );
+    }
+    Ok(())
+}
+
+/// Compares the versions declared in `files` with `expected`, the version of the
+/// first file.
+fn check(files: &[PathBuf], names: &[String], expected: &Version, json: bool) -> io::Result<()> {
+    let mut found = Vec::new();
+    for (path, name) in files.iter().zip(names) {
+        for version in read_versions(path)? {
+            let matches = version.parse::<Version>().is_ok_and(|v| v == *expected);
+            found.push((name.as_str(), version, matches));
+        }
+    }
+    let consistent = found.iter().all(|(_, _, matches)| *matches);
+
+    if json {
+        let versions: Vec<serde_json::Value> = found
+            .iter()
+            .map(|(file, version, _)| serde_json::json!({ "file": file, "version": version }))
+            .collect();
+        let output = serde_json::json!({
+            "version": expected.to_string(),
+            "consistent": consistent,
+            "versions": versions,
+        });
+        println!("{}", output);
+    } else if consistent {
+        println!(
+            "All version files agree on {} ({}).",
+            expected,
+            names.join(", ")
+        );
+    }
+
+    if consistent {
+        return Ok(());
+    }
+    let mismatches: Vec<String> = found
+        .iter()
+        .filter(|(_, _, matches)| !matches)
+        .map(|(file, version, _)| format!("{} has {}", file, version))
+        .collect();
+    Err(io::Error::new(
+        io::ErrorKind::InvalidData,
+        format!(
+            "Versions disagree with {} in {}: {}.",
+            expected,
+            names[0],
+            mismatches.join(", ")
+        ),
+    ))
+}
+
+/// `hajime version` for projects that take the version from git tags: bumping
+/// or setting the version creates an annotated tag instead of editing files.
+fn run_git(root: &Path, action: Action, dry_run: bool, json: bool) -> io::Result<()> {
+    let name = read_name(&root.join("pyproject.toml"))?;
+    // Setting a version also works before the first tag
+    let git_version = match action {
+        Action::Set(_) => git_version(root).ok(),
+        _ => Some(git_version(root)?),
+    };
+    let current = git_version.as_ref().map(|g| g.current.to_string());
+
+    let new_version = match (action, &git_version) {
+        (Action::Set(version), _) => version,
+        (_, None) => unreachable!("only Set runs without a tag"),
+        (Action::Show, Some(git_version)) => {
+            if json {
+                let output = serde_json::json!({
+                    "name": name,
+                    "version": current,
+                    "tag": format!("v{}", git_version.tag),
+                });
+                println!("{}", output);
+            } else {
+                println!("{}", git_version.current);
+            }
+            return Ok(());
+        }
+        (Action::Check, Some(git_version)) => {
+            println!(
+                "The version ({}) comes from git tags; there are no version files to compare.",
+                git_version.current
+            );
+            return Ok(());
+        }
+        (Action::Bump(level), Some(git_version)) => git_version
+            .tag
+            .bump(level)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?,
+        (Action::AutoBump, Some(git_version)) => git_version
+            .tag
+            .bump(changelog::release_level(root, &git_version.tag)?)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?,
+    };
+
+    let tag = if dry_run {
+        format!("v{}", new_version)
+    } else {
+        create_tag(root, &new_version)?
+    };
+    let current = current.unwrap_or_else(|| "untagged".to_string());
+    if json {
+        let output = serde_json::json!({
+            "name": name,
+            "previous": current,
+            "version": new_version.to_string(),
+            "tag": tag,
+            "dry_run": dry_run,
+        });
+        println!("{}", output);
+    } else if dry_run {
+        println!("Would tag {} (the version is {}).", tag, current);
+    } else {
+        println!("Created tag {} (the version was {}).", tag, current);
+        println!("Push it with `git push origin {}`.", tag);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn v(s: &str) -> Version {
+        s.parse().unwrap()
+    }
+
+    fn bump(s: &str, level: BumpLevel) -> String {
+        v(s).bump(level).unwrap().to_string()
+    }
+
+    #[test]
+    fn parse_normalizes_spellings() {
+        let cases = [
+            ("1.0", "1.0"),
+            ("v1.2.3", "1.2.3"),
+            ("1!2.0", "1!2.0"),
+            ("1.0-alpha.1", "1.0a1"),
+            ("1.0.BETA2", "1.0b2"),
+            ("1.0c1", "1.0rc1"),
+            ("1.0preview", "1.0rc0"),
+            ("1.0-1", "1.0.post1"),
+            ("1.0.rev2", "1.0.post2"),
+            ("1.0dev", "1.0.dev0"),
+            ("1.0rc1.post2.dev3", "1.0rc1.post2.dev3"),
+            ("1.0+Ubuntu-1_2", "1.0+ubuntu.1.2"),
+        ];
+        for (input, normalized) in cases {
+            assert_eq!(v(input).to_string(), normalized, "{}", input);
+        }
+    }
+
+    #[test]
+    fn parse_rejects_invalid_versions() {
+        for input in ["", "one", "1.0x", "1..0", "1.0+", "1.0+a..b", "1.0-"] {
+            assert!(input.parse::<Version>().is_err(), "{}", input);
+        }
+    }
+
+    #[test]
+    fn ordering_follows_pep_440() {
+        let ordered = [
+            "1.0.dev456",
+            "1.0a1",
+            "1.0a2.dev456",
+            "1.0a12.dev456",
+            "1.0a12",
+            "1.0b1.dev456",
+            "1.0b2",
+            "1.0b2.post345.dev456",
+            "1.0b2.post345",
+            "1.0rc1.dev456",
+            "1.0rc1",
+            "1.0",
+            "1.0+abc.5",
+            "1.0+abc.7",
+            "1.0+5",
+            "1.0.post456.dev34",
+            "1.0.post456",
+            "1.1.dev1",
+            "1!0.1",
+        ];
+        for pair in ordered.windows(2) {
+            assert!(v(pair[0]) < v(pair[1]), "{} < {}", pair[0], pair[1]);
+        }
+        assert_eq!(v("1.0"), v("1.0.0"));
+        assert_eq!(v("1.0a1"), v("1.0.0alpha1"));
+    }
+
+    #[test]
+    fn bump_release_levels() {
+        assert_eq!(bump("1.2.3", BumpLevel::Major), "2.0.0");
+        assert_eq!(bump("1.2.3", BumpLevel::Minor), "1.3.0");
+        assert_eq!(bump("1.2.3", BumpLevel::Patch), "1.2.4");
+        assert_eq!(bump("1.0", BumpLevel::Patch), "1.0.1");
+        assert_eq!(bump("1.2.3+local", BumpLevel::Patch), "1.2.4");
+        assert_eq!(bump("1.2.3.post1", BumpLevel::Patch), "1.2.4");
+    }
+
+    #[test]
+    fn bump_release_finalizes_earlier_versions() {
+        assert_eq!(bump("2.0.0rc1", BumpLevel::Major), "2.0.0");
+        assert_eq!(bump("2.0.0rc1", BumpLevel::Patch), "2.0.0");
+        assert_eq!(bump("1.0rc1.post1", BumpLevel::Patch), "1.0");
+        assert_eq!(bump("1.2.0.dev3", BumpLevel::Minor), "1.2.0");
+        assert_eq!(bump("1.2.1rc1", BumpLevel::Minor), "1.3.0");
+    }
+
+    #[test]
+    fn bump_pre_releases() {
+        assert_eq!(bump("1.2.3", BumpLevel::Alpha), "1.2.4a0");
+        assert_eq!(bump("1.2.4a0", BumpLevel::Alpha), "1.2.4a1");
+        assert_eq!(bump("1.2.4a1", BumpLevel::Beta), "1.2.4b0");
+        assert_eq!(bump("1.2.4b3", BumpLevel::Pre), "1.2.4b4");
+        assert_eq!(bump("1.2.4a1.dev2", BumpLevel::Alpha), "1.2.4a1");
+        assert_eq!(bump("1.2.4a1.dev2", BumpLevel::Pre), "1.2.4a1");
+        assert_eq!(bump("1.2.4a1.dev2", BumpLevel::Rc), "1.2.4rc0");
+        assert_eq!(bump("1.2.4.dev2", BumpLevel::Alpha), "1.2.4a0");
+    }
+
+    #[test]
+    fn bump_post_and_dev_releases() {
+        assert_eq!(bump("1.0", BumpLevel::Post), "1.0.post0");
+        assert_eq!(bump("1.0.post0", BumpLevel::Post), "1.0.post1");
+        assert_eq!(bump("1.0.post1.dev0", BumpLevel::Post), "1.0.post1");
+        assert_eq!(bump("1.0", BumpLevel::Dev), "1.0.1.dev0");
+        assert_eq!(bump("1.0.1.dev0", BumpLevel::Dev), "1.0.1.dev1");
+        assert_eq!(bump("1.0a1", BumpLevel::Dev), "1.0a2.dev0");
+        assert_eq!(bump("1.0.post1", BumpLevel::Dev), "1.0.post2.dev0");
+    }
+
+    #[test]
+    fn bump_rejects_lower_versions() {
+        assert!(v("1.0rc1").bump(BumpLevel::Beta).is_err());
+        assert!(v("1.0rc1").bump(BumpLevel::Alpha).is_err());
+        assert!(v("1.0").bump(BumpLevel::Pre).is_err());
+    }
+
+    #[test]
+    fn semver_form() {
+        assert_eq!(v("1.2").to_semver().unwrap(), "1.2.0");
+        assert_eq!(v("1.2.0rc1").to_semver().unwrap(), "1.2.0-rc.1");
+        assert_eq!(v("1.2.0b2+abc").to_semver().unwrap(), "1.2.0-beta.2+abc");
+        assert!(v("1.2.0.post1").to_semver().is_err());
+        assert!(v("1.2.3.4").to_semver().is_err());
+    }
+}
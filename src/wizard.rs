@@ -0,0 +1,74 @@
+use crate::backend::Backend;
+use crate::cli::CliFramework;
+use crate::helpers::{ask, ask_choice, ask_yes_no};
+use crate::new::{validate_python_version, NewOptions, DEFAULT_PYTHON_VERSION};
+use std::io;
+use std::path::Path;
+
+/// Interactively asks for the project settings of `hajime new -i`.
+/// Values already given on the command line are offered as defaults; the author
+/// defaults come from the git configuration seen from `dir`.
+pub fn run(dir: &Path, project_name: &str, options: &mut NewOptions) -> io::Result<()> {
+    println!(
+        "Setting up '{}'. Press Enter to accept the value in brackets.\n",
+        project_name
+    );
+
+    options.description = Some(ask("Description", &options.description(project_name))?);
+    options.author = Some(ask("Author name", &options.author_name(dir))?);
+    options.author_email = Some(ask("Author email", &options.author_email(dir))?);
+
+    let license = ask(
+        "License (SPDX identifier, empty for none)",
+        options.license.as_deref().unwrap_or_default(),
+    )?;
+    options.license = (!license.is_empty()).then_some(license);
+
+    let default_python = options
+        .python_version
+        .clone()
+        .unwrap_or(DEFAULT_PYTHON_VERSION.to_string());
+    options.python_version = Some(loop {
+        let version = ask("Minimum Python version", &default_python)?;
+        match validate_python_version(&version) {
+            Ok(()) => break version,
+            Err(e) => println!("{}", e),
+        }
+    });
+
+    // Rust extensions can only be built with maturin
+    if options.rust {
+        options.backend = Some(Backend::Maturin);
+    } else {
+        let backends: Vec<String> = Backend::ALL.iter().map(Backend::to_string).collect();
+        let backends: Vec<&str> = backends.iter().map(String::as_str).collect();
+        let default_backend = options.backend.unwrap_or_default().to_string();
+        let backend = ask_choice("Build backend", &backends, &default_backend)?;
+        options.backend = Some(backend.parse().expect("one of the listed backends"));
+    }
+
+    if !options.is_rust() {
+        let layout = ask_choice("Layout", &["flat", "src"], &options.layout.to_string())?;
+        options.layout = layout.parse().expect("one of the listed layouts");
+    }
+
+    let frameworks: Vec<String> = CliFramework::ALL
+        .iter()
+        .map(CliFramework::to_string)
+        .collect();
+    let mut choices = vec!["none"];
+    choices.extend(frameworks.iter().map(String::as_str));
+    let default_cli = options
+        .cli
+        .map_or("none".to_string(), |cli| cli.to_string());
+    let cli = ask_choice("Command-line interface", &choices, &default_cli)?;
+    options.cli = cli.parse().ok();
+    if options.cli.is_none() {
+        options.entry_point = ask_yes_no(
+            "Register greet.hello as a console script?",
+            options.entry_point,
+        )?;
+    }
+    println!();
+    Ok(())
+}
@@ -0,0 +1,297 @@
+use crate::gitignore;
+use crate::helpers::requirement_name;
+use crate::naming;
+use crate::new::{init_git, NewOptions};
+use crate::pyproject::{self, string_array};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use toml_edit::{value, Array, DocumentMut};
+
+/// Members glob written to new workspace roots; new members are created below it.
+const MEMBERS_DIR: &str = "packages";
+
+/// A package of a uv workspace.
+pub struct Member {
+    /// Normalized distribution name.
+    pub name: String,
+    pub dir: PathBuf,
+    /// Normalized names of the packages this member depends on.
+    dependencies: Vec<String>,
+}
+
+fn is_workspace_root(dir: &Path) -> bool {
+    pyproject::read(&dir.join("pyproject.toml")).is_ok_and(|doc| {
+        doc.get("tool")
+            .and_then(|t| t.get("uv"))
+            .and_then(|u| u.get("workspace"))
+            .is_some()
+    })
+}
+
+/// The nearest directory at or above `start` whose `pyproject.toml` has `[tool.uv.workspace]`.
+pub fn find_root(start: &Path) -> Option<PathBuf> {
+    let start = start.canonicalize().ok()?;
+    start
+        .ancestors()
+        .find(|dir| is_workspace_root(dir))
+        .map(Path::to_path_buf)
+}
+
+fn string_list(doc: &DocumentMut, key: &str) -> Vec<String> {
+    doc.get("tool")
+        .and_then(|t| t.get("uv"))
+        .and_then(|u| u.get("workspace"))
+        .and_then(|w| w.get(key))
+        .and_then(|m| m.as_array())
+        .map(|a| {
+            a.iter()
+                .filter_map(|v| v.as_str().map(str::to_string))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Matches `name` against a pattern where `*` stands for any run of characters.
+fn wildcard_match(pattern: &str, name: &str) -> bool {
+    match pattern.split_once('*') {
+        None => pattern == name,
+        Some((prefix, rest)) => {
+            let Some(remainder) = name.strip_prefix(prefix) else {
+                return false;
+            };
+            (0..=remainder.len())
+                .filter(|i| remainder.is_char_boundary(*i))
+                .any(|i| wildcard_match(rest, &remainder[i..]))
+        }
+    }
+}
+
+/// Directories below `root` matching a members glob such as `packages/*`.
+fn glob_dirs(root: &Path, pattern: &str) -> Vec<PathBuf> {
+    let mut dirs = vec![root.to_path_buf()];
+    for component in pattern.split('/').filter(|c| !c.is_empty() && *c != ".") {
+        let mut next = Vec::new();
+        for dir in &dirs {
+            if !component.contains('*') {
+                next.push(dir.join(component));
+                continue;
+            }
+            let Ok(entries) = fs::read_dir(dir) else {
+                continue;
+            };
+            let mut matches: Vec<PathBuf> = entries
+                .filter_map(|e| e.ok())
+                .filter(|e| e.path().is_dir())
+                .filter(|e| wildcard_match(component, &e.file_name().to_string_lossy()))
+                .map(|e| e.path())
+                .collect();
+            matches.sort();
+            next.extend(matches);
+        }
+        dirs = next;
+    }
+    dirs.into_iter().filter(|d| d.is_dir()).collect()
+}
+
+/// Reads the members of the workspace at `root`, in dependency order:
+/// every member comes after the workspace members it depends on.
+pub fn members(root: &Path) -> io::Result<Vec<Member>> {
+    let doc = pyproject::read(&root.join("pyproject.toml"))?;
+    let excluded: Vec<PathBuf> = string_list(&doc, "exclude")
+        .iter()
+        .flat_map(|pattern| glob_dirs(root, pattern))
+        .collect();
+
+    let mut members: Vec<Member> = Vec::new();
+    for pattern in string_list(&doc, "members") {
+        for dir in glob_dirs(root, &pattern) {
+            if excluded.contains(&dir) || members.iter().any(|m| m.dir == dir) {
+                continue;
+            }
+            let Ok(member) = pyproject::read(&dir.join("pyproject.toml")) else {
+                continue;
+            };
+            let Some(project) = member.get("project") else {
+                continue;
+            };
+            let name = project
+                .get("name")
+                .and_then(|n| n.as_str())
+                .map(naming::normalize)
+                .unwrap_or_default();
+            let dependencies = project
+                .get("dependencies")
+                .and_then(|d| d.as_array())
+                .map(|a| {
+                    a.iter()
+                        .filter_map(|d| d.as_str())
+                        .map(|d| naming::normalize(&requirement_name(d)))
+                        .collect()
+                })
+                .unwrap_or_default();
+            members.push(Member {
+                name,
+                dir,
+                dependencies,
+            });
+        }
+    }
+
+    dependency_order(members)
+}
+
+/// Sorts `members` so that dependencies come first; errors on cycles.
+fn dependency_order(mut members: Vec<Member>) -> io::Result<Vec<Member>> {
+    members.sort_by(|a, b| a.name.cmp(&b.name));
+    let names: Vec<String> = members.iter().map(|m| m.name.clone()).collect();
+
+    let mut ordered: Vec<Member> = Vec::new();
+    while !members.is_empty() {
+        let ready = members.iter().position(|m| {
+            m.dependencies
+                .iter()
+                .all(|d| *d == m.name || !names.contains(d) || ordered.iter().any(|o| o.name == *d))
+        });
+        match ready {
+            Some(index) => ordered.push(members.remove(index)),
+            None => {
+                let remaining: Vec<&str> = members.iter().map(|m| m.name.as_str()).collect();
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!(
+                        "Workspace members depend on each other in a cycle: {}",
+                        remaining.join(", ")
+                    ),
+                ));
+            }
+        }
+    }
+    Ok(ordered)
+}
+
+/// The project directories a command should run in: `start`, one
+/// workspace member (`package`), or every member in dependency order (`all`).
+pub fn project_dirs(start: &Path, package: Option<&str>, all: bool) -> io::Result<Vec<PathBuf>> {
+    if package.is_none() && !all {
+        return Ok(vec![start.to_path_buf()]);
+    }
+
+    let root = find_root(start).ok_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::NotFound,
+            "Not inside a uv workspace ([tool.uv.workspace] in pyproject.toml).",
+        )
+    })?;
+    let members = members(&root)?;
+    match package {
+        Some(package) => {
+            let wanted = naming::normalize(package);
+            members
+                .into_iter()
+                .find(|m| m.name == wanted)
+                .map(|m| vec![m.dir])
+                .ok_or_else(|| {
+                    io::Error::new(
+                        io::ErrorKind::NotFound,
+                        format!("No workspace member named '{}'.", package),
+                    )
+                })
+        }
+        None => Ok(members.into_iter().map(|m| m.dir).collect()),
+    }
+}
+
+/// Creates the root of a uv workspace: a `pyproject.toml` with
+/// `[tool.uv.workspace]`, a README, a `.gitignore` and a Git repository.
+pub fn create_workspace(parent: &Path, name: &str, options: &NewOptions) -> io::Result<()> {
+    let root = parent.join(name);
+    let root = root.as_path();
+    if root.exists() {
+        return Err(io::Error::new(
+            io::ErrorKind::AlreadyExists,
+            format!("'{}' already exists.", name),
+        ));
+    }
+    if options.dry_run {
+        println!("Would create workspace '{}':", name);
+        println!(
+            "{}/\n├── .gitignore\n├── README.md\n├── {}/\n└── pyproject.toml",
+            name, MEMBERS_DIR
+        );
+        return Ok(());
+    }
+
+    fs::create_dir_all(root.join(MEMBERS_DIR))?;
+    let mut doc = DocumentMut::new();
+    let workspace = pyproject::table_mut(&mut doc, &["tool", "uv", "workspace"]);
+    workspace["members"] = value(string_array(&[format!("{}/*", MEMBERS_DIR)]));
+    pyproject::write(&root.join("pyproject.toml"), &doc)?;
+    fs::write(
+        root.join("README.md"),
+        format!(
+            "# {}\n\nA uv workspace. Add packages with `hajime new <name>` from this directory.\n",
+            name
+        ),
+    )?;
+    gitignore::write(root, &options.gitignore_presets())?;
+    init_git(root, options)?;
+
+    println!("\nWorkspace '{}' created successfully!", name);
+    println!(
+        "Add a package with:\n    cd {}\n    hajime new <name>",
+        name
+    );
+    Ok(())
+}
+
+/// Where `hajime new <name>` creates a project inside the workspace at `root`:
+/// below the directory of the first `dir/*` members glob, else next to the root
+/// `pyproject.toml`.
+pub fn member_path(root: &Path, name: &str) -> io::Result<PathBuf> {
+    let doc = pyproject::read(&root.join("pyproject.toml"))?;
+    let parent = string_list(&doc, "members")
+        .iter()
+        .find_map(|p| p.strip_suffix("/*").map(str::to_string))
+        .filter(|p| !p.contains('*'))
+        .map(PathBuf::from)
+        .unwrap_or_default();
+    Ok(root.join(parent).join(name))
+}
+
+/// Adds `dir` to the members of the workspace at `root` unless a members glob
+/// already covers it.
+pub fn register_member(root: &Path, dir: &Path) -> io::Result<()> {
+    let pyproject_path = root.join("pyproject.toml");
+    let mut doc = pyproject::read(&pyproject_path)?;
+    let relative = dir.strip_prefix(root).unwrap_or(dir);
+    let path: Vec<String> = relative
+        .components()
+        .map(|c| c.as_os_str().to_string_lossy().into_owned())
+        .collect();
+
+    let covered = string_list(&doc, "members").iter().any(|pattern| {
+        let components: Vec<&str> = pattern
+            .split('/')
+            .filter(|c| !c.is_empty() && *c != ".")
+            .collect();
+        components.len() == path.len()
+            && components
+                .iter()
+                .zip(&path)
+                .all(|(pattern, name)| wildcard_match(pattern, name))
+    });
+    if covered {
+        return Ok(());
+    }
+
+    let workspace = pyproject::table_mut(&mut doc, &["tool", "uv", "workspace"]);
+    let mut members = workspace
+        .get("members")
+        .and_then(|m| m.as_array())
+        .cloned()
+        .unwrap_or_else(Array::new);
+    members.push(path.join("/"));
+    workspace["members"] = value(members);
+    pyproject::write(&pyproject_path, &doc)
+}
@@ -0,0 +1,13 @@
+"""Command-line interface for {{project_name}}."""
+
+from __future__ import annotations
+
+import argparse
+
+
+def main(argv: list[str] | None = None) -> int:
+    parser = argparse.ArgumentParser(prog="{{project_name}}")
+    parser.add_argument("name", nargs="?", default="World", help="who to greet")
+    args = parser.parse_args(argv)
+    print(f"Hello, {args.name}!")
+    return 0
@@ -0,0 +1,19 @@
+import subprocess
+import sys
+
+from {{module_name}}.cli import main
+
+
+def test_main(capsys):
+    assert main(["test"]) == 0
+    assert capsys.readouterr().out == "Hello, test!\n"
+
+
+def test_module_invocation():
+    result = subprocess.run(
+        [sys.executable, "-m", "{{module_name}}", "test"],
+        capture_output=True,
+        text=True,
+        check=True,
+    )
+    assert result.stdout == "Hello, test!\n"
@@ -0,0 +1,3 @@
+from {{module_name}}.cli import main
+
+main(prog_name="{{project_name}}")
@@ -0,0 +1,10 @@
+"""Command-line interface for {{project_name}}."""
+
+import click
+
+
+@click.command()
+@click.argument("name", default="World")
+def main(name: str) -> None:
+    """Greet NAME."""
+    click.echo(f"Hello, {name}!")
@@ -0,0 +1,22 @@
+import subprocess
+import sys
+
+from click.testing import CliRunner
+
+from {{module_name}}.cli import main
+
+
+def test_main():
+    result = CliRunner().invoke(main, ["test"])
+    assert result.exit_code == 0
+    assert result.output == "Hello, test!\n"
+
+
+def test_module_invocation():
+    result = subprocess.run(
+        [sys.executable, "-m", "{{module_name}}", "test"],
+        capture_output=True,
+        text=True,
+        check=True,
+    )
+    assert result.stdout == "Hello, test!\n"
@@ -0,0 +1,3 @@
+from {{module_name}}.cli import app
+
+app(prog_name="{{project_name}}")
@@ -0,0 +1,11 @@
+"""Command-line interface for {{project_name}}."""
+
+import typer
+
+app = typer.Typer()
+
+
+@app.command()
+def main(name: str = typer.Argument("World")) -> None:
+    """Greet NAME."""
+    typer.echo(f"Hello, {name}!")
@@ -0,0 +1,6 @@
+def hello(name: str = "world") -> None:
+    print(f"Hello, {name}!")
+
+
+if __name__ == "__main__":
+    hello()
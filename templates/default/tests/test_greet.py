@@ -0,0 +1,5 @@
+from {{module_name}}.greet import hello
+
+
+def test_hello():
+    hello("test")  # Should print 'Hello, test!'
@@ -0,0 +1,3 @@
+from ._core import hello
+
+__all__ = ["hello"]
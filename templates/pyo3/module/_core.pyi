@@ -0,0 +1 @@
+def hello(name: str = "world") -> str: ...
@@ -0,0 +1,15 @@
+use pyo3::prelude::*;
+
+/// Returns a greeting for `name`.
+#[pyfunction]
+#[pyo3(signature = (name = "world"))]
+fn hello(name: &str) -> String {
+    format!("Hello, {name}!")
+}
+
+/// The compiled extension module, importable as `{{module_name}}._core`.
+#[pymodule]
+fn _core(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_function(wrap_pyfunction!(hello, m)?)?;
+    Ok(())
+}
@@ -0,0 +1,9 @@
+from {{module_name}} import _core, hello
+
+
+def test_hello():
+    assert hello("test") == "Hello, test!"
+
+
+def test_core_module():
+    assert _core.hello() == "Hello, world!"